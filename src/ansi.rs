@@ -0,0 +1,128 @@
+use egui::Color32;
+
+/// One colored run of text within a console row.
+#[derive(Clone)]
+pub struct LogSpan {
+    pub text: String,
+    pub color: Option<Color32>,
+    pub bold: bool,
+}
+
+/// A single row of `Kernel::logs`: one `sys.stdout.write()` call (or one
+/// formatted traceback), split into colored spans.
+pub type LogLine = Vec<LogSpan>;
+
+/// Scans `s` for `\x1b[...m` SGR escapes, tracking the current foreground
+/// color and bold state, and returns the plain-text runs between them.
+pub fn parse_ansi_spans(s: &str) -> LogLine {
+    let mut spans = Vec::new();
+    let mut color: Option<Color32> = None;
+    let mut bold = false;
+    let mut buf = String::new();
+
+    let mut chars = s.chars().peekable();
+    while let Some(c) = chars.next() {
+        if c == '\x1b' && chars.peek() == Some(&'[') {
+            chars.next(); // consume '['
+            let mut code = String::new();
+            for c in chars.by_ref() {
+                if c == 'm' {
+                    break;
+                }
+                code.push(c);
+            }
+
+            if !buf.is_empty() {
+                spans.push(LogSpan {
+                    text: std::mem::take(&mut buf),
+                    color,
+                    bold,
+                });
+            }
+
+            if code.is_empty() {
+                apply_sgr(0, &mut color, &mut bold);
+            } else {
+                for part in code.split(';') {
+                    apply_sgr(part.parse().unwrap_or(0), &mut color, &mut bold);
+                }
+            }
+        } else {
+            buf.push(c);
+        }
+    }
+
+    if !buf.is_empty() {
+        spans.push(LogSpan {
+            text: buf,
+            color,
+            bold,
+        });
+    }
+
+    spans
+}
+
+fn apply_sgr(code: u32, color: &mut Option<Color32>, bold: &mut bool) {
+    match code {
+        0 => {
+            *color = None;
+            *bold = false;
+        }
+        1 => *bold = true,
+        22 => *bold = false,
+        30..=37 => *color = Some(standard_color(code - 30, *bold)),
+        39 => *color = None,
+        90..=97 => *color = Some(standard_color(code - 90, true)),
+        _ => {}
+    }
+}
+
+/// Maps one of the 8 base ANSI color indices to a `Color32`, using the
+/// brighter variant for bold text or the `9x` "bright" codes.
+fn standard_color(index: u32, bright: bool) -> Color32 {
+    const NORMAL: [Color32; 8] = [
+        Color32::from_rgb(0, 0, 0),
+        Color32::from_rgb(205, 49, 49),
+        Color32::from_rgb(13, 188, 121),
+        Color32::from_rgb(229, 229, 16),
+        Color32::from_rgb(36, 114, 200),
+        Color32::from_rgb(188, 63, 188),
+        Color32::from_rgb(17, 168, 205),
+        Color32::from_rgb(229, 229, 229),
+    ];
+    const BRIGHT: [Color32; 8] = [
+        Color32::from_rgb(102, 102, 102),
+        Color32::from_rgb(241, 76, 76),
+        Color32::from_rgb(35, 209, 139),
+        Color32::from_rgb(245, 245, 67),
+        Color32::from_rgb(59, 142, 234),
+        Color32::from_rgb(214, 112, 214),
+        Color32::from_rgb(41, 184, 219),
+        Color32::from_rgb(229, 229, 229),
+    ];
+    (if bright { BRIGHT } else { NORMAL })[index as usize % 8]
+}
+
+/// Renders one parsed console row as a single `egui::Label`, preserving the
+/// per-span foreground color and using a brighter shade in place of real
+/// bold weight (the code editor's monospace font has no bold cut).
+pub fn show_log_line(ui: &mut egui::Ui, line: &LogLine) {
+    let mut job = egui::text::LayoutJob::default();
+    for span in line {
+        let mut color = span.color.unwrap_or_else(|| ui.visuals().text_color());
+        if span.bold {
+            color = color.gamma_multiply(1.3);
+        }
+        job.append(
+            &span.text,
+            0.0,
+            egui::TextFormat {
+                font_id: egui::FontId::monospace(12.0),
+                color,
+                ..Default::default()
+            },
+        );
+    }
+    ui.label(job);
+}
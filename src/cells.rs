@@ -0,0 +1,33 @@
+//! Jupyter-style `# %%` cell boundaries, shared between the in-editor
+//! "Run cell" feature and the `.ipynb` exporter.
+
+/// Byte ranges of each `# %%`-delimited cell in `source`. The marker line (if
+/// any) is part of the cell it introduces, matching Jupyter/VS Code.
+pub fn cell_boundaries(source: &str) -> Vec<std::ops::Range<usize>> {
+    let mut starts = vec![0usize];
+    let mut offset = 0usize;
+    for line in source.split_inclusive('\n') {
+        if offset != 0 && line.trim_start().starts_with("# %%") {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+    starts.push(source.len());
+    starts.windows(2).map(|w| w[0]..w[1]).collect()
+}
+
+/// The cell containing character offset `char_idx` of `source`, as a byte range.
+pub fn cell_at_char(source: &str, char_idx: usize) -> std::ops::Range<usize> {
+    let byte_idx = source
+        .char_indices()
+        .nth(char_idx)
+        .map(|(i, _)| i)
+        .unwrap_or(source.len());
+
+    let bounds = cell_boundaries(source);
+    bounds
+        .iter()
+        .find(|range| range.contains(&byte_idx))
+        .cloned()
+        .unwrap_or_else(|| bounds.last().cloned().unwrap_or(0..source.len()))
+}
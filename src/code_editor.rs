@@ -1,12 +1,26 @@
 use egui::{Id, Response, TextEdit, Ui};
 use egui_extras::syntax_highlighting::{highlight, CodeTheme};
 
+/// Result of [`code_editor_with_autoindent`]: the underlying widget response
+/// plus the cursor position, so callers can figure out e.g. which cell the
+/// cursor is sitting in.
+pub struct EditorOutput {
+    pub response: Response,
+    pub cursor_range: Option<egui::text::CursorRange>,
+}
+
+/// A line starting a new Jupyter-style `# %%` cell, drawn with a subtle
+/// separator above it.
+fn is_cell_marker(line: &str) -> bool {
+    line.trim_start().starts_with("# %%")
+}
+
 pub fn code_editor_with_autoindent(
     ui: &mut Ui,
     id: Id,
     code: &mut String,
     lang: &'static str,
-) -> Response {
+) -> EditorOutput {
     let mut layouter = move |ui: &Ui, string: &str, wrap_width: f32| {
         let mut layout_job = highlight(
             ui.ctx(),
@@ -60,5 +74,31 @@ pub fn code_editor_with_autoindent(
         }
     }
 
-    ret.response
+    // Draw a subtle separator above every `# %%` cell boundary. The layouter
+    // wraps long lines at `wrap_width`, so one source line can span several
+    // galley rows: only a row that follows a newline starts the next line.
+    let lines: Vec<&str> = code.split('\n').collect();
+    let mut line_idx = 0;
+    let mut starts_line = true;
+    for row in &ret.galley.rows {
+        let is_boundary =
+            starts_line && line_idx != 0 && lines.get(line_idx).is_some_and(|l| is_cell_marker(l));
+        starts_line = row.ends_with_newline;
+        if row.ends_with_newline {
+            line_idx += 1;
+        }
+        if is_boundary {
+            let y = ret.galley_pos.y + row.rect.top();
+            let x_min = ret.galley_pos.x;
+            let x_max = ret.galley_pos.x + ret.galley.rect.width();
+            let color = ui.visuals().weak_text_color().gamma_multiply(0.5);
+            ui.painter()
+                .hline(x_min..=x_max, y, egui::Stroke::new(1.0, color));
+        }
+    }
+
+    EditorOutput {
+        response: ret.response,
+        cursor_range: ret.cursor_range,
+    }
 }
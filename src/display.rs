@@ -0,0 +1,97 @@
+use rustpython_vm::builtins::{PyBytes, PyBytesRef};
+
+/// One emitted rich-display output, as recorded by [`pydisplay::display`] and
+/// friends. The host (`TemplateApp`) decodes/renders these in the output
+/// panel; an item with a `display_id` matching an earlier one replaces it in
+/// place, which is how progress bars / animations update.
+pub enum DisplayKind {
+    Png(Vec<u8>),
+    Markdown(String),
+    Html(String),
+    Text(String),
+}
+
+pub struct DisplayItem {
+    pub id: Option<String>,
+    pub kind: DisplayKind,
+}
+
+#[rustpython_vm::pymodule]
+pub mod pydisplay {
+    use super::*;
+    use std::cell::{LazyCell, RefCell};
+
+    use rustpython_vm::{function::OptionalArg, PyObjectRef, PyResult, VirtualMachine};
+
+    thread_local! {
+        static ITEMS: LazyCell<RefCell<Vec<DisplayItem>>> = LazyCell::new(RefCell::default);
+    }
+
+    fn push(id: Option<String>, kind: DisplayKind) {
+        ITEMS.with(|items| (**items).borrow_mut().push(DisplayItem { id, kind }));
+    }
+
+    fn kind_from_obj(
+        obj: &PyObjectRef,
+        mime: Option<&str>,
+        vm: &VirtualMachine,
+    ) -> PyResult<DisplayKind> {
+        match mime {
+            Some("image/png") | Some("image/jpeg") => Ok(DisplayKind::Png(bytes_of(obj, vm)?)),
+            Some("text/markdown") => Ok(DisplayKind::Markdown(obj.str(vm)?.to_string())),
+            Some("text/html") => Ok(DisplayKind::Html(obj.str(vm)?.to_string())),
+            _ => {
+                if let Some(bytes) = obj.payload::<PyBytes>() {
+                    Ok(DisplayKind::Png(bytes.as_bytes().to_vec()))
+                } else {
+                    Ok(DisplayKind::Text(obj.str(vm)?.to_string()))
+                }
+            }
+        }
+    }
+
+    fn bytes_of(obj: &PyObjectRef, vm: &VirtualMachine) -> PyResult<Vec<u8>> {
+        obj.payload::<PyBytes>()
+            .map(|b| b.as_bytes().to_vec())
+            .ok_or_else(|| vm.new_type_error("display() of an image requires bytes".to_owned()))
+    }
+
+    /// IPython-style `display(obj, mime=None, display_id=None)`. Without an
+    /// explicit `mime`, bytes are treated as an image and anything else is
+    /// stringified.
+    #[pyfunction]
+    fn display(
+        obj: PyObjectRef,
+        mime: OptionalArg<String>,
+        display_id: OptionalArg<String>,
+        vm: &VirtualMachine,
+    ) -> PyResult<()> {
+        let kind = kind_from_obj(&obj, mime.into_option().as_deref(), vm)?;
+        push(display_id.into_option(), kind);
+        Ok(())
+    }
+
+    #[pyfunction]
+    fn display_png(data: PyBytesRef, display_id: OptionalArg<String>) -> PyResult<()> {
+        push(display_id.into_option(), DisplayKind::Png(data.as_bytes().to_vec()));
+        Ok(())
+    }
+
+    #[pyfunction]
+    fn display_markdown(text: String, display_id: OptionalArg<String>) -> PyResult<()> {
+        push(display_id.into_option(), DisplayKind::Markdown(text));
+        Ok(())
+    }
+
+    #[pyfunction]
+    fn display_html(text: String, display_id: OptionalArg<String>) -> PyResult<()> {
+        push(display_id.into_option(), DisplayKind::Html(text));
+        Ok(())
+    }
+
+    pub fn dump_items() -> Vec<DisplayItem> {
+        ITEMS.with(|items| std::mem::take(&mut *(**items).borrow_mut()))
+    }
+}
+
+pub use pydisplay::{dump_items, make_module};
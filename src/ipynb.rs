@@ -0,0 +1,128 @@
+//! Exports a session as an nbformat 4 `.ipynb` document, so results can be
+//! handed off to real Jupyter/Colab.
+
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine as _};
+use serde_json::{json, Value};
+
+use crate::ansi::LogLine;
+use crate::cells::cell_boundaries;
+use crate::display::{DisplayItem, DisplayKind};
+
+/// Serializes `source` (split on `# %%` boundaries, or a single cell if
+/// there are none) into an nbformat 4 notebook, attaching the session's
+/// captured console `logs`, rich `display()` outputs, and the current plot
+/// (pre-rendered to SVG by the caller) as outputs on the final cell.
+pub fn export_session(
+    source: &str,
+    logs: &[LogLine],
+    display_items: &[DisplayItem],
+    plot_svg: Option<&str>,
+) -> String {
+    let mut cells: Vec<Value> = cell_boundaries(source)
+        .into_iter()
+        .map(|range| code_cell(&source[range]))
+        .collect();
+
+    // The kernel doesn't track which cell produced which output, so the
+    // accumulated session outputs all land on the last cell.
+    if let Some(last) = cells.last_mut() {
+        if let Some(outputs) = last["outputs"].as_array_mut() {
+            outputs.extend(stream_outputs(logs));
+            outputs.extend(display_outputs(display_items));
+            if let Some(svg) = plot_svg {
+                outputs.push(svg_output(svg));
+            }
+        }
+    }
+
+    let notebook = json!({
+        "cells": cells,
+        "metadata": {
+            "kernelspec": {
+                "display_name": "Python 3",
+                "language": "python",
+                "name": "python3"
+            },
+            "language_info": {
+                "name": "python",
+                "version": "3"
+            }
+        },
+        "nbformat": 4,
+        "nbformat_minor": 5
+    });
+
+    serde_json::to_string_pretty(&notebook).unwrap_or_default()
+}
+
+fn code_cell(source: &str) -> Value {
+    json!({
+        "cell_type": "code",
+        "execution_count": Value::Null,
+        "metadata": {},
+        "outputs": Vec::<Value>::new(),
+        "source": source_lines(source),
+    })
+}
+
+/// nbformat wants `source`/text outputs as a list of lines, each keeping its
+/// trailing `\n` (except possibly the very last line).
+fn source_lines(text: &str) -> Vec<String> {
+    text.split_inclusive('\n').map(str::to_owned).collect()
+}
+
+fn stream_outputs(logs: &[LogLine]) -> Vec<Value> {
+    if logs.is_empty() {
+        return vec![];
+    }
+
+    // Each `LogLine` is one `sys.stdout.write()` call and already ends in
+    // whatever newline that write produced, so concatenate directly —
+    // joining with "\n" would insert an extra blank line between writes.
+    let text: String = logs
+        .iter()
+        .flat_map(|line| line.iter().map(|span| span.text.as_str()))
+        .collect();
+
+    vec![json!({
+        "output_type": "stream",
+        "name": "stdout",
+        "text": source_lines(&text),
+    })]
+}
+
+fn display_outputs(items: &[DisplayItem]) -> Vec<Value> {
+    items
+        .iter()
+        .map(|item| match &item.kind {
+            DisplayKind::Png(bytes) => json!({
+                "output_type": "display_data",
+                "data": { "image/png": BASE64.encode(bytes) },
+                "metadata": {}
+            }),
+            DisplayKind::Markdown(text) => json!({
+                "output_type": "display_data",
+                "data": { "text/markdown": source_lines(text) },
+                "metadata": {}
+            }),
+            DisplayKind::Html(text) => json!({
+                "output_type": "display_data",
+                "data": { "text/html": source_lines(text) },
+                "metadata": {}
+            }),
+            DisplayKind::Text(text) => json!({
+                "output_type": "display_data",
+                "data": { "text/plain": source_lines(text) },
+                "metadata": {}
+            }),
+        })
+        .collect()
+}
+
+fn svg_output(svg: &str) -> Value {
+    json!({
+        "output_type": "display_data",
+        "data": { "image/svg+xml": source_lines(svg) },
+        "metadata": {}
+    })
+}
@@ -1,25 +1,31 @@
 use std::{
     cell::RefCell,
     rc::Rc,
-    sync::{Arc, Mutex},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Arc, Mutex,
+    },
+    time::{Duration, Instant},
 };
 
 use egui::{Color32, RichText, ScrollArea, Vec2};
 use egui_plotter::EguiBackend;
 use plotters_svg::SVGBackend;
-use rustpython_plotters::{draw_plots, PlotCommand};
-use plotters::{
-    chart::ChartBuilder,
-    prelude::{DrawingBackend, IntoDrawingArea, PathElement},
-    series::LineSeries,
-    style::{Color, IntoFont, BLACK, RED, WHITE},
-};
+use plotters::prelude::IntoDrawingArea;
+use plot::{draw_plots, PlotCommand};
 use rfd::AsyncFileDialog;
 use rustpython_vm::{
-    builtins::PyCode, scope::Scope, Interpreter, PyObjectRef, PyRef, VirtualMachine,
+    builtins::PyCode, function::FuncArgs, scope::Scope, Interpreter, PyObjectRef, PyRef,
+    VirtualMachine,
 };
 
+mod ansi;
+mod cells;
 mod code_editor;
+mod display;
+mod ipynb;
+mod plot;
+mod pyndarray;
 
 // When compiling natively:
 #[cfg(not(target_arch = "wasm32"))]
@@ -92,12 +98,15 @@ fn main() {
 }
 
 type LoadFileEvent = Arc<Mutex<Option<(String, String)>>>;
-type Logs = Rc<RefCell<Vec<String>>>;
+type Logs = Rc<RefCell<Vec<ansi::LogLine>>>;
 
 pub struct TemplateApp {
     save_data: SaveData,
 
     plot_info: Vec<PlotCommand>,
+    display_items: Vec<display::DisplayItem>,
+    image_textures: std::collections::HashMap<String, egui::TextureHandle>,
+    commonmark_cache: egui_commonmark::CommonMarkCache,
     load_file_event: LoadFileEvent,
     kernel: Kernel,
 }
@@ -107,6 +116,9 @@ pub struct Kernel {
     interpreter: Interpreter,
     scope: Scope,
     code_obj: Option<PyRef<PyCode>>,
+    interrupt: Arc<AtomicBool>,
+    deadline: Arc<Mutex<Option<Instant>>>,
+    last_run_elapsed: Option<Duration>,
 }
 
 #[derive(serde::Deserialize, serde::Serialize)]
@@ -115,6 +127,8 @@ pub struct SaveData {
     file_name: String,
     source_code: String,
     run_schedule: RunSchedule,
+    /// Per-`run()` time budget, in seconds; `None` disables the auto-stop.
+    time_budget_secs: Option<f32>,
 }
 
 #[derive(serde::Deserialize, serde::Serialize, Clone, Copy, PartialEq, Eq, Default)]
@@ -132,6 +146,7 @@ impl Default for SaveData {
             file_name: "example_project.py".into(),
             source_code: include_str!("example_project.py").to_owned(),
             run_schedule: RunSchedule::default(),
+            time_budget_secs: None,
         }
     }
 }
@@ -152,6 +167,9 @@ impl TemplateApp {
             save_data,
             load_file_event: Default::default(),
             plot_info: vec![],
+            display_items: vec![],
+            image_textures: Default::default(),
+            commonmark_cache: Default::default(),
         }
     }
 }
@@ -176,6 +194,10 @@ impl eframe::App for TemplateApp {
             }
         };
 
+        let run_cell_shortcut =
+            egui::KeyboardShortcut::new(egui::Modifiers::COMMAND, egui::Key::Enter);
+        let mut run_cell_requested = ctx.input_mut(|i| i.consume_shortcut(&run_cell_shortcut));
+
         egui::TopBottomPanel::top("top_panel").show(ctx, |ui| {
             egui::menu::bar(ui, |ui| {
                 ui.menu_button("File", |ui| {
@@ -223,6 +245,28 @@ impl eframe::App for TemplateApp {
                         RunSchedule::EachFrame,
                         "Continuous",
                     );
+
+                    ui.separator();
+
+                    let mut has_budget = self.save_data.time_budget_secs.is_some();
+                    if ui
+                        .checkbox(&mut has_budget, "Time budget")
+                        .on_hover_text(
+                            "Running a script blocks the whole UI, so this is the only way to \
+                             recover from a runaway loop short of restarting the app.",
+                        )
+                        .changed()
+                    {
+                        self.save_data.time_budget_secs = has_budget.then_some(5.0);
+                    }
+                    if let Some(secs) = &mut self.save_data.time_budget_secs {
+                        ui.add(
+                            egui::DragValue::new(secs)
+                                .range(0.1..=60.0)
+                                .suffix("s")
+                                .speed(0.1),
+                        );
+                    }
                 });
 
                 ui.menu_button("Export", |ui| {
@@ -231,11 +275,35 @@ impl eframe::App for TemplateApp {
                         let svg = SVGBackend::with_string(&mut s, (1000, 1000));
                         let drawing = svg.into_drawing_area();
                         if let Err(e) = draw_plots(&drawing, &self.plot_info) {
-                            self.kernel.logs.borrow_mut().push(e.to_string());
+                            self.kernel
+                                .logs
+                                .borrow_mut()
+                                .push(ansi::parse_ansi_spans(&e.to_string()));
                         }
                         drop(drawing);
                         save_file(&s, "output.svg");
                     }
+
+                    if ui.button("Jupyter Notebook (.ipynb)").clicked() {
+                        let mut svg = String::new();
+                        let drawing =
+                            SVGBackend::with_string(&mut svg, (1000, 1000)).into_drawing_area();
+                        if let Err(e) = draw_plots(&drawing, &self.plot_info) {
+                            self.kernel
+                                .logs
+                                .borrow_mut()
+                                .push(ansi::parse_ansi_spans(&e.to_string()));
+                        }
+                        drop(drawing);
+
+                        let notebook = ipynb::export_session(
+                            &self.save_data.source_code,
+                            &self.kernel.logs.borrow(),
+                            &self.display_items,
+                            (!svg.is_empty()).then_some(svg.as_str()),
+                        );
+                        save_file(&notebook, "output.ipynb");
+                    }
                 });
 
                 ui.with_layout(egui::Layout::right_to_left(Default::default()), |ui| {
@@ -259,10 +327,16 @@ impl eframe::App for TemplateApp {
                     ui.painter().rect_filled(rect, 0.0, Color32::WHITE);
 
                     if let Err(e) = draw_plots(&area, &self.plot_info) {
-                        self.kernel.logs.borrow_mut().push(e.to_string());
+                        self.kernel
+                            .logs
+                            .borrow_mut()
+                            .push(ansi::parse_ansi_spans(&e.to_string()));
                     }
 
-
+                    if !self.display_items.is_empty() {
+                        ui.separator();
+                        self.show_display_items(ui, ctx);
+                    }
                 });
             });
 
@@ -274,6 +348,14 @@ impl eframe::App for TemplateApp {
                         do_run = true;
                     }
 
+                    if ui
+                        .button(RichText::new("Run cell ▶").size(22.0).strong())
+                        .on_hover_text("Run just the cell under the cursor (Ctrl/Cmd+Enter)")
+                        .clicked()
+                    {
+                        run_cell_requested = true;
+                    }
+
                     if ui.button(RichText::new("Reset ↺").size(22.0).strong()).clicked() {
                         self.kernel = Kernel::new_with_code(self.save_data.source_code.clone())
                     }
@@ -281,6 +363,9 @@ impl eframe::App for TemplateApp {
 
                 ui.horizontal(|ui| {
                     ui.heading("Console");
+                    if let Some(elapsed) = self.kernel.last_run_elapsed() {
+                        ui.weak(format!("last run: {:.1}s", elapsed.as_secs_f32()));
+                    }
                     ui.with_layout(egui::Layout::right_to_left(Default::default()), |ui| {
                         if ui.button("Clear").clicked() {
                             self.kernel.logs.borrow_mut().clear();
@@ -295,7 +380,7 @@ impl eframe::App for TemplateApp {
                         .auto_shrink(false)
                         .show_rows(ui, 18.0, n, |ui, range| {
                             for row in &self.kernel.logs.borrow()[range] {
-                                ui.label(row);
+                                ansi::show_log_line(ui, row);
                             }
                         });
                 });
@@ -322,7 +407,7 @@ impl eframe::App for TemplateApp {
 
         let editor_resp = editor_resp.unwrap();
         let mut new_code_loaded = false;
-        if editor_resp.changed() {
+        if editor_resp.response.changed() {
             //self.kernel.load(self.save_data.source_code.clone());
             new_code_loaded |= self.kernel.load(self.save_data.source_code.clone());
         }
@@ -331,9 +416,96 @@ impl eframe::App for TemplateApp {
             do_run = true;
         }
 
+        let time_budget = self.save_data.time_budget_secs.map(Duration::from_secs_f32);
+
         if do_run {
-            self.kernel.run();
-            self.plot_info = rustpython_plotters::dump_commands();
+            self.kernel.run(time_budget);
+            self.plot_info = plot::pyplotter::dump_commands();
+            self.merge_display_items(display::dump_items());
+        }
+
+        if run_cell_requested {
+            let char_idx = editor_resp
+                .cursor_range
+                .map(|cursor| cursor.primary.ccursor.index)
+                .unwrap_or(0);
+            let cell = cells::cell_at_char(&self.save_data.source_code, char_idx);
+            self.kernel
+                .run_cell(&self.save_data.source_code[cell], time_budget);
+            self.plot_info = plot::pyplotter::dump_commands();
+            self.merge_display_items(display::dump_items());
+        }
+    }
+}
+
+impl TemplateApp {
+    /// Folds freshly emitted display items into `self.display_items`, replacing
+    /// any earlier item that shares a `display_id` so progress-bar/animation
+    /// style updates show in place instead of piling up.
+    fn merge_display_items(&mut self, new_items: Vec<display::DisplayItem>) {
+        for item in new_items {
+            if let Some(id) = &item.id {
+                if let Some(existing) = self
+                    .display_items
+                    .iter_mut()
+                    .find(|existing| existing.id.as_deref() == Some(id.as_str()))
+                {
+                    *existing = item;
+                    continue;
+                }
+            }
+            self.display_items.push(item);
+        }
+    }
+
+    /// Renders `self.display_items` in the output panel: images are decoded
+    /// and shown as textures, markdown through `egui_commonmark`, and
+    /// HTML/plain text as a monospace fallback.
+    fn show_display_items(&mut self, ui: &mut egui::Ui, ctx: &egui::Context) {
+        for (idx, item) in self.display_items.iter().enumerate() {
+            let key = item
+                .id
+                .clone()
+                .unwrap_or_else(|| format!("__anon_{idx}"));
+
+            match &item.kind {
+                display::DisplayKind::Png(bytes) => match image::load_from_memory(bytes) {
+                    Ok(decoded) => {
+                        let rgba = decoded.to_rgba8();
+                        let (w, h) = rgba.dimensions();
+                        let color_image =
+                            egui::ColorImage::from_rgba_unmultiplied([w as usize, h as usize], &rgba);
+
+                        if let Some(texture) = self.image_textures.get_mut(&key) {
+                            texture.set(color_image, Default::default());
+                        } else {
+                            let texture =
+                                ctx.load_texture(key.clone(), color_image, Default::default());
+                            self.image_textures.insert(key.clone(), texture);
+                        }
+
+                        let texture = &self.image_textures[&key];
+                        let scale = (ui.available_width() / w as f32).min(1.0);
+                        ui.image((
+                            texture.id(),
+                            Vec2::new(w as f32 * scale, h as f32 * scale),
+                        ));
+                    }
+                    Err(e) => {
+                        ui.label(format!("<failed to decode image: {e}>"));
+                    }
+                },
+                display::DisplayKind::Markdown(text) => {
+                    egui_commonmark::CommonMarkViewer::new().show(
+                        ui,
+                        &mut self.commonmark_cache,
+                        text,
+                    );
+                }
+                display::DisplayKind::Html(text) | display::DisplayKind::Text(text) => {
+                    ui.monospace(text);
+                }
+            }
         }
     }
 }
@@ -417,13 +589,75 @@ fn install_stdout(vm: &VirtualMachine, logs: Logs) {
 
     let stdout = anon_object(vm, "InternalStdout");
 
-    let writer = vm.new_function("write", move |s: String| logs.borrow_mut().push(s));
+    let writer =
+        vm.new_function("write", move |s: String| logs.borrow_mut().push(ansi::parse_ansi_spans(&s)));
 
     stdout.set_attr("write", writer, vm).unwrap();
 
     sys.set_attr("stdout", stdout.clone(), vm).unwrap();
 }
 
+/// Installs a `sys.settrace` hook that checks `deadline` (the per-run time
+/// budget, if any) on every line/call event, raising `KeyboardInterrupt` to
+/// cooperatively abort a runaway script once its budget is spent.
+///
+/// `exec` runs `vm.run_code_obj` synchronously on the UI thread, so this is
+/// the *only* thing that can break out of a script once it's running: there
+/// is no way for a click (or anything else) to reach `interrupt` while the
+/// trace hook's own thread is blocked inside the call it would need to
+/// interrupt. `interrupt` still exists as the flag this hook latches once
+/// tripped (so a caught-and-retried `KeyboardInterrupt` keeps failing instead
+/// of resuming), but nothing sets it from outside the hook itself.
+fn install_interrupt_hook(
+    vm: &VirtualMachine,
+    interrupt: Arc<AtomicBool>,
+    deadline: Arc<Mutex<Option<Instant>>>,
+) {
+    // `sys.settrace`'s global trace function is only consulted for `call`
+    // events; to keep receiving `line` events within a frame (the only way
+    // a tight `while True: pass` loop with no further calls ever re-enters
+    // this check) it has to return a *local* trace function from each `call`
+    // event. Returning the frame object itself (what `args` holds) satisfies
+    // the type checker but isn't callable, so tracing silently stops after
+    // the first `call` and the interrupt/deadline check never fires again.
+    // `self_ref` lets the closure hand back a reference to itself.
+    let self_ref: Rc<RefCell<Option<PyObjectRef>>> = Rc::new(RefCell::new(None));
+    let trace_fn = {
+        let self_ref = self_ref.clone();
+        vm.new_function("__stupyder_interrupt_check", move |vm: &VirtualMachine, _args: FuncArgs| {
+            let past_deadline = deadline
+                .lock()
+                .unwrap()
+                .is_some_and(|deadline| Instant::now() >= deadline);
+
+            if interrupt.load(Ordering::Relaxed) || past_deadline {
+                interrupt.store(true, Ordering::Relaxed);
+                return Err(vm.new_exception_msg(
+                    vm.ctx.exceptions.keyboard_interrupt.to_owned(),
+                    "Execution interrupted".to_owned(),
+                ));
+            }
+
+            Ok(self_ref.borrow().clone().unwrap_or_else(|| vm.ctx.none()))
+        })
+    };
+    *self_ref.borrow_mut() = Some(trace_fn.clone().into());
+
+    match vm.import("sys", 0) {
+        Ok(sys) => match sys.get_attr("settrace", vm) {
+            Ok(settrace) => {
+                let _ = settrace.call((trace_fn,), vm);
+            }
+            Err(_) => {
+                // This RustPython build doesn't implement `sys.settrace`:
+                // the time budget becomes a no-op instead of failing kernel
+                // startup.
+            }
+        },
+        Err(_) => {}
+    }
+}
+
 impl Kernel {
     pub fn new() -> Self {
         let interpreter = Interpreter::with_init(Default::default(), |vm| {
@@ -434,22 +668,28 @@ impl Kernel {
                 Box::new(rust_py_module::make_module),
             );
             */
+            vm.add_native_module("ndarray".to_owned(), Box::new(pyndarray::make_module));
             vm.add_native_module(
-                "ndarray".to_owned(),
-                Box::new(rustpython_ndarray::make_module),
+                "pyplotters".to_owned(),
+                Box::new(plot::pyplotter::make_module),
             );
+            vm.add_native_module("display".to_owned(), Box::new(display::make_module));
+            vm.add_native_module("matplotlib".to_owned(), Box::new(plot::matplotlib::make_module));
             vm.add_native_module(
-                "pyplotters".to_owned(),
-                Box::new(rustpython_plotters::make_module),
+                "matplotlib.pyplot".to_owned(),
+                Box::new(plot::matplotlib_pyplot::make_module),
             )
         });
 
         let logs = Logs::default();
+        let interrupt = Arc::new(AtomicBool::new(false));
+        let deadline = Arc::new(Mutex::new(None));
 
         let scope = interpreter.enter(|vm| {
             // Create scope
             let scope = vm.new_scope_with_builtins();
             install_stdout(vm, logs.clone());
+            install_interrupt_hook(vm, interrupt.clone(), deadline.clone());
 
             scope
         });
@@ -459,6 +699,9 @@ impl Kernel {
             interpreter,
             logs,
             code_obj: None,
+            interrupt,
+            deadline,
+            last_run_elapsed: None,
         }
     }
 
@@ -485,18 +728,65 @@ impl Kernel {
                 Err(compile_err) => {
                     self.logs
                         .borrow_mut()
-                        .push(format!("Compile error: {:#?}", compile_err));
+                        .push(ansi::parse_ansi_spans(&format!(
+                            "Compile error: {:#?}",
+                            compile_err
+                        )));
                     false
                 }
             }
         })
     }
 
-    pub fn run(&mut self) {
+    pub fn run(&mut self, time_budget: Option<Duration>) {
         let Some(code) = self.code_obj.clone() else {
             return;
         };
 
+        self.exec(code, time_budget);
+    }
+
+    /// Compiles and runs just `cell_source` (one `# %%` cell) against the
+    /// kernel's persistent `scope`, so variables defined in previously run
+    /// cells stay alive.
+    pub fn run_cell(&mut self, cell_source: &str, time_budget: Option<Duration>) {
+        let compiled = self.interpreter.enter(|vm| {
+            vm.compile(
+                cell_source,
+                rustpython_vm::compiler::Mode::Exec,
+                "<cell>".to_owned(),
+            )
+        });
+
+        match compiled {
+            Ok(code) => self.exec(code, time_budget),
+            Err(compile_err) => {
+                self.logs
+                    .borrow_mut()
+                    .push(ansi::parse_ansi_spans(&format!(
+                        "Compile error: {:#?}",
+                        compile_err
+                    )));
+            }
+        }
+    }
+
+    /// How long the most recently finished `run`/`run_cell` call took. There
+    /// is deliberately no live "running…" readout: `exec` blocks the UI
+    /// thread for its whole duration, so nothing can repaint a spinner while
+    /// a script is actually in flight. This is only ever `Some` for the run
+    /// that just completed, surfaced as a "last run: Ns" readout instead.
+    pub fn last_run_elapsed(&self) -> Option<Duration> {
+        self.last_run_elapsed
+    }
+
+    fn exec(&mut self, code: PyRef<PyCode>, time_budget: Option<Duration>) {
+        // Cleared up front so a script that's caught and suppressed a
+        // previous run's `KeyboardInterrupt` doesn't start out pre-tripped.
+        self.interrupt.store(false, Ordering::Relaxed);
+        *self.deadline.lock().unwrap() = time_budget.map(|budget| Instant::now() + budget);
+
+        let started = Instant::now();
         let scope = self.scope.clone();
         let error = self.interpreter.enter(move |vm| {
             if let Err(exec_err) = vm.run_code_obj(code, scope) {
@@ -507,9 +797,12 @@ impl Kernel {
                 None
             }
         });
+        self.last_run_elapsed = Some(started.elapsed());
 
         if let Some(e) = error {
-            self.logs.borrow_mut().push(format!("Error: {e}"));
+            self.logs
+                .borrow_mut()
+                .push(ansi::parse_ansi_spans(&format!("Error: {e}")));
         }
     }
 }
@@ -1,25 +1,139 @@
-use rustpython_ndarray::pyndarray::PyNdArrayFloat64;
+use crate::pyndarray::PyNdArrayFloat64;
+use plotters::{
+    chart::ChartBuilder,
+    prelude::{DrawingArea, DrawingBackend, IntoDrawingArea},
+    series::LineSeries,
+    style::{Color, RGBColor, BLUE, CYAN, GREEN, MAGENTA, RED},
+};
+
+/// Per-series styling accepted as kwargs on `plot`/`scatter`/`bar`/`hist`.
+#[derive(Clone, Default)]
+pub struct SeriesStyle {
+    pub label: Option<String>,
+    pub color: Option<RGBColor>,
+}
+
+impl SeriesStyle {
+    fn color_or(&self, default: RGBColor) -> RGBColor {
+        self.color.unwrap_or(default)
+    }
+}
+
+fn parse_color(name: &str) -> Option<RGBColor> {
+    Some(match name {
+        "red" => RED,
+        "blue" => BLUE,
+        "green" => GREEN,
+        "cyan" => CYAN,
+        "magenta" => MAGENTA,
+        _ => return None,
+    })
+}
+
+use std::cell::{LazyCell, RefCell};
+
+use rustpython_vm::{function::KwArgs, PyObjectRef, PyResult, VirtualMachine};
+
+thread_local! {
+    static COMMANDS: LazyCell<RefCell<Vec<PlotCommand>>> = LazyCell::new(RefCell::default);
+}
+
+/// Queues one plotting call, shared by the `pyplotters` and
+/// `matplotlib.pyplot` native modules.
+fn push(cmd: PlotCommand) {
+    COMMANDS.with(|reader| (**reader).borrow_mut().push(cmd));
+}
+
+fn style_from_kwargs(mut kw: KwArgs, vm: &VirtualMachine) -> PyResult<SeriesStyle> {
+    let label = kw
+        .pop_kwarg("label")
+        .map(|s| s.str(vm).map(|s| s.to_string()))
+        .transpose()?;
+    let color = kw
+        .pop_kwarg("color")
+        .map(|c| c.str(vm).map(|s| s.to_string()))
+        .transpose()?
+        .and_then(|name| parse_color(&name));
+    Ok(SeriesStyle { label, color })
+}
+
+fn as_float64(obj: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyNdArrayFloat64> {
+    obj.downcast::<PyNdArrayFloat64>()
+        .map(|arr| (*arr).clone())
+        .map_err(|_| vm.new_runtime_error("array must be float64".into()))
+}
 
 #[rustpython_vm::pymodule]
 pub mod pyplotter {
     use super::*;
-    use std::cell::{LazyCell, RefCell};
-    use std::borrow::BorrowMut;
 
-    use rustpython_vm::{PyObjectRef, PyResult, VirtualMachine};
+    #[pyfunction]
+    fn plot(x: PyObjectRef, y: PyObjectRef, kw: KwArgs, vm: &VirtualMachine) -> PyResult<()> {
+        let x = as_float64(x, vm)?;
+        let y = as_float64(y, vm)?;
+        let style = style_from_kwargs(kw, vm)?;
+        push(PlotCommand::PlotXY { x, y, style });
+        Ok(())
+    }
+
+    #[pyfunction]
+    fn scatter(x: PyObjectRef, y: PyObjectRef, kw: KwArgs, vm: &VirtualMachine) -> PyResult<()> {
+        let x = as_float64(x, vm)?;
+        let y = as_float64(y, vm)?;
+        let style = style_from_kwargs(kw, vm)?;
+        push(PlotCommand::Scatter { x, y, style });
+        Ok(())
+    }
+
+    #[pyfunction]
+    fn bar(x: PyObjectRef, heights: PyObjectRef, kw: KwArgs, vm: &VirtualMachine) -> PyResult<()> {
+        let x = as_float64(x, vm)?;
+        let heights = as_float64(heights, vm)?;
+        let style = style_from_kwargs(kw, vm)?;
+        push(PlotCommand::Bar { x, heights, style });
+        Ok(())
+    }
 
-    thread_local! {
-        static COMMANDS: LazyCell<RefCell<Vec<PlotCommand>>> = LazyCell::new(RefCell::default);
+    #[pyfunction]
+    fn hist(values: PyObjectRef, bins: rustpython_vm::function::OptionalArg<usize>, kw: KwArgs, vm: &VirtualMachine) -> PyResult<()> {
+        let values = as_float64(values, vm)?;
+        let bins = bins.unwrap_or(10).max(1);
+        let style = style_from_kwargs(kw, vm)?;
+        push(PlotCommand::Hist {
+            values,
+            bins,
+            style,
+        });
+        Ok(())
+    }
+
+    #[pyfunction]
+    fn title(text: String) -> PyResult<()> {
+        push(PlotCommand::Title(text));
+        Ok(())
     }
 
     #[pyfunction]
-    fn plot(x: PyObjectRef, y: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
-        let x = x.downcast::<PyNdArrayFloat64>().map_err(|_| vm.new_runtime_error("X Must be float32".into()))?;
-        let y = y.downcast::<PyNdArrayFloat64>().map_err(|_| vm.new_runtime_error("Y Must be float32".into()))?;
-        COMMANDS.with(|reader| (**reader).borrow_mut().push(PlotCommand::PlotXY { 
-            x: (*x).clone(),
-            y: (*y).clone(),
-        }));
+    fn xlabel(text: String) -> PyResult<()> {
+        push(PlotCommand::XLabel(text));
+        Ok(())
+    }
+
+    #[pyfunction]
+    fn ylabel(text: String) -> PyResult<()> {
+        push(PlotCommand::YLabel(text));
+        Ok(())
+    }
+
+    #[pyfunction]
+    fn legend() -> PyResult<()> {
+        push(PlotCommand::Legend);
+        Ok(())
+    }
+
+    #[pyfunction]
+    fn clear() -> PyResult<()> {
+        COMMANDS.with(|r| (**r).borrow_mut().clear());
         Ok(())
     }
 
@@ -28,393 +142,305 @@ pub mod pyplotter {
     }
 }
 
-pub enum PlotCommand {
-    PlotXY {
-        x: PyNdArrayFloat64, 
-        y: PyNdArrayFloat64,
-    },
-}
+/// Empty `matplotlib` package, so `import matplotlib.pyplot as plt` can
+/// resolve `matplotlib` before reaching the real `pyplot` module below.
+#[rustpython_vm::pymodule]
+pub mod matplotlib {}
 
-/*
-   #[derive(PyPayload, Clone, Debug)]
-   #[pyclass(module = "pyndarray", name)]
-   pub struct $dtype {
-   pub(crate) arr: SlicedArcArray<$primitive>,
-   }
-
-   impl GenericArray for SlicedArcArray<$primitive> {
-   type PyArray = $dtype;
-   const DTYPE: DataType = $dtype_enum;
-   fn cast(&self) -> Self::PyArray {
-   $dtype { arr: self.clone() }
-   }
-   }
-
-   #[pyclass]
-   impl $dtype {
-// AsMapping methods
-#[pymethod(magic)]
-fn getitem(&self, needle: PyObjectRef, vm: &VirtualMachine) -> PyResult {
-self.arr.getitem(needle, vm)
-}
+/// A `matplotlib.pyplot`-compatible surface over the same `PlotCommand`
+/// queue as [`pyplotter`], so scripts written against real matplotlib
+/// render through the existing `draw_plots` pipeline unmodified.
+#[rustpython_vm::pymodule]
+pub mod matplotlib_pyplot {
+    use super::*;
 
-#[pymethod(magic)]428016b3948d04c4395929a89a89172ba5b8364e
-fn setitem(
-&self,
-needle: PyObjectRef,
-value: PyObjectRef,
-vm: &VirtualMachine,
-) -> PyResult<()> {
-let slice = py_index_to_sliceinfo(needle, vm)?;
-self.assign_or_elem_fn(
-slice,
-value,
-vm,
-|mut dest, src, _| Ok(dest.assign(&src)),
-|mut dest, value, _| Ok(dest.fill(value)),
-)
-}
+    #[pyfunction]
+    fn plot(x: PyObjectRef, y: PyObjectRef, kw: KwArgs, vm: &VirtualMachine) -> PyResult<()> {
+        let x = as_float64(x, vm)?;
+        let y = as_float64(y, vm)?;
+        let style = style_from_kwargs(kw, vm)?;
+        push(PlotCommand::PlotXY { x, y, style });
+        Ok(())
+    }
 
-#[pymethod(magic)]
-fn len(
-&self,
-_vm: &VirtualMachine,
-) -> PyResult<PyInt> {
-let len = self.arr.read(|sliced| sliced.len());
-Ok(len.into())
-}
+    #[pyfunction]
+    fn scatter(x: PyObjectRef, y: PyObjectRef, kw: KwArgs, vm: &VirtualMachine) -> PyResult<()> {
+        let x = as_float64(x, vm)?;
+        let y = as_float64(y, vm)?;
+        let style = style_from_kwargs(kw, vm)?;
+        push(PlotCommand::Scatter { x, y, style });
+        Ok(())
+    }
 
-// Stringy methods
-#[pymethod(magic)]
-fn str(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyResult<PyStrRef> {
-Ok(vm.ctx.new_str(zelf.arr.to_string()))
-}
+    #[pyfunction]
+    fn bar(x: PyObjectRef, heights: PyObjectRef, kw: KwArgs, vm: &VirtualMachine) -> PyResult<()> {
+        let x = as_float64(x, vm)?;
+        let heights = as_float64(heights, vm)?;
+        let style = style_from_kwargs(kw, vm)?;
+        push(PlotCommand::Bar { x, heights, style });
+        Ok(())
+    }
 
-#[pymethod(magic)]
-fn repr(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyResult<PyStrRef> {
-Ok(vm.ctx.new_str(zelf.arr.repr()))
-}
+    #[pyfunction]
+    fn title(text: String) -> PyResult<()> {
+        push(PlotCommand::Title(text));
+        Ok(())
+    }
 
-// AsNumber methods
-#[pymethod(magic)]
-fn iadd(
-zelf: PyRef<Self>,
-other: PyObjectRef,
-vm: &VirtualMachine,
-) -> PyResult<()> {
-let empty_slice = empty_slice_like(&zelf.arr);
-zelf.assign_or_elem_fn(
-empty_slice,
-other,
-vm,
-    |mut dest, src, _vm| Ok(dest += &src),
-    |mut dest, value, _vm| Ok(dest += value),
-    )
+    #[pyfunction]
+    fn xlabel(text: String) -> PyResult<()> {
+        push(PlotCommand::XLabel(text));
+        Ok(())
     }
 
-#[pymethod(magic)]
-fn add(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
-    let inst = $dtype {
-        arr: zelf.arr.sliced_copy(),
-    };
-    let inst = inst.into_ref(&vm.ctx);
-    $dtype::iadd(inst.clone(), other, vm)?;
-    Ok(inst.into())
-}
+    #[pyfunction]
+    fn ylabel(text: String) -> PyResult<()> {
+        push(PlotCommand::YLabel(text));
+        Ok(())
+    }
 
-#[pymethod(magic)]
-fn isub(
-    zelf: PyRef<Self>,
-    other: PyObjectRef,
-    vm: &VirtualMachine,
-) -> PyResult<()> {
-    let empty_slice = empty_slice_like(&zelf.arr);
-    zelf.assign_or_elem_fn(
-        empty_slice,
-        other,
-        vm,
-        |mut dest, src, _vm| Ok(dest -= &src),
-        |mut dest, value, _vm| Ok(dest -= value),
-    )
-}
+    #[pyfunction]
+    fn xlim(lo: f64, hi: f64) -> PyResult<()> {
+        push(PlotCommand::XLim(lo, hi));
+        Ok(())
+    }
 
-#[pymethod(magic)]
-fn sub(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
-    let inst = $dtype {
-        arr: zelf.arr.sliced_copy(),
-    };
-    let inst = inst.into_ref(&vm.ctx);
-    $dtype::isub(inst.clone(), other, vm)?;
-    Ok(inst.into())
-}
+    #[pyfunction]
+    fn ylim(lo: f64, hi: f64) -> PyResult<()> {
+        push(PlotCommand::YLim(lo, hi));
+        Ok(())
+    }
 
-#[pymethod(magic)]
-fn itruediv(
-    zelf: PyRef<Self>,
-    other: PyObjectRef,
-    vm: &VirtualMachine,
-) -> PyResult<()> {
-    let empty_slice = empty_slice_like(&zelf.arr);
-    zelf.assign_or_elem_fn(
-        empty_slice,
-        other,
-        vm,
-        |mut dest, src, _vm| Ok(dest /= &src),
-        |mut dest, value, _vm| Ok(dest /= value),
-    )
-}
+    #[pyfunction]
+    fn legend() -> PyResult<()> {
+        push(PlotCommand::Legend);
+        Ok(())
+    }
 
-#[pymethod(magic)]
-fn truediv(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
-    let inst = $dtype {
-        arr: zelf.arr.sliced_copy(),
-    };
-    let inst = inst.into_ref(&vm.ctx);
-    $dtype::itruediv(inst.clone(), other, vm)?;
-    Ok(inst.into())
-}
+    /// `plt.figure()` starts a fresh canvas, same as `pyplotters.clear()`.
+    #[pyfunction]
+    fn figure() -> PyResult<()> {
+        COMMANDS.with(|r| (**r).borrow_mut().clear());
+        Ok(())
+    }
 
-#[pymethod(magic)]
-fn imul(
-    zelf: PyRef<Self>,
-    other: PyObjectRef,
-    vm: &VirtualMachine,
-) -> PyResult<()> {
-    let empty_slice = empty_slice_like(&zelf.arr);
-    zelf.assign_or_elem_fn(
-        empty_slice,
-        other,
-        vm,
-        |mut dest, src, _vm| Ok(dest *= &src),
-        |mut dest, value, _vm| Ok(dest *= value),
-    )
+    /// A no-op: `draw_plots` already re-renders the queued commands every
+    /// frame, so there's no separate "show the figure" step to perform.
+    #[pyfunction]
+    fn show() -> PyResult<()> {
+        Ok(())
+    }
 }
 
-#[pymethod(magic)]
-fn mul(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
-    let inst = $dtype {
-        arr: zelf.arr.sliced_copy(),
-    };
-    let inst = inst.into_ref(&vm.ctx);
-    $dtype::imul(inst.clone(), other, vm)?;
-    Ok(inst.into())
+/// One recorded plotting call. The host drains these via `dump_commands()`
+/// and `draw_plots` reconstructs a full figure from them.
+pub enum PlotCommand {
+    PlotXY {
+        x: PyNdArrayFloat64,
+        y: PyNdArrayFloat64,
+        style: SeriesStyle,
+    },
+    Scatter {
+        x: PyNdArrayFloat64,
+        y: PyNdArrayFloat64,
+        style: SeriesStyle,
+    },
+    Bar {
+        x: PyNdArrayFloat64,
+        heights: PyNdArrayFloat64,
+        style: SeriesStyle,
+    },
+    Hist {
+        values: PyNdArrayFloat64,
+        bins: usize,
+        style: SeriesStyle,
+    },
+    Title(String),
+    XLabel(String),
+    YLabel(String),
+    XLim(f64, f64),
+    YLim(f64, f64),
+    Legend,
 }
+
+fn to_vec(arr: &PyNdArrayFloat64) -> Vec<f64> {
+    arr.arr.read(|arr| arr.iter().copied().collect())
 }
 
-impl $dtype {
-    pub fn assign_or_elem_fn<F, G, U>(
-        &self,
-        slice: DynamicSlice,
-        value: PyObjectRef,
-        vm: &VirtualMachine,
-        assign_fn: F,
-        elem_fn: G,
-    ) -> PyResult<U>
-        where
-        F: Fn(
-            ArrayViewMutD<'_, $primitive>,
-            ArrayViewD<'_, $primitive>,
-            &VirtualMachine,
-        ) -> PyResult<U>,
-    G: Fn(
-        ArrayViewMutD<'_, $primitive>,
-        $primitive,
-        &VirtualMachine,
-    ) -> PyResult<U>,
-    {
-        if let Some(other_array) = value.downcast_ref::<$dtype>() {
-            self.arr
-                .assign_fn(slice, other_array.arr.clone(), vm, assign_fn)
-        } else {
-            let value: $primitive = TryFromObject::try_from_object(vm, value)?;
-            self.arr.write(|mut sliced| {
-                if let Err(e) = sliced.bounds_check(&slice) {
-                    return Err(
-                        vm.new_runtime_error(format!("Slice out of bounds; {e}"))
-                    );
-                }
+/// Computes `bins` equal-width histogram `(edges, counts)` over `values`.
+fn histogram(values: &[f64], bins: usize) -> (Vec<f64>, Vec<f64>) {
+    let lo = values.iter().copied().fold(f64::INFINITY, f64::min);
+    let hi = values.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let width = if hi > lo { (hi - lo) / bins as f64 } else { 1.0 };
 
-                elem_fn(sliced.slice_mut(&slice), value, vm)
-            })
-        }
+    let mut counts = vec![0.0; bins];
+    for &v in values {
+        let idx = (((v - lo) / width) as usize).min(bins - 1);
+        counts[idx] += 1.0;
     }
+    let edges = (0..=bins).map(|i| lo + width * i as f64).collect();
+    (edges, counts)
 }
 
-impl AsMapping for $dtype {
-    fn as_mapping() -> &'static PyMappingMethods {
-        static AS_MAPPING: PyMappingMethods = PyMappingMethods {
-            subscript: atomic_func!(|mapping, needle, vm| {
-                $dtype::mapping_downcast(mapping).getitem(needle.to_pyobject(vm), vm)
-            }),
-            ass_subscript: atomic_func!(|mapping, needle, value, vm| {
-                let zelf = $dtype::mapping_downcast(mapping);
-                if let Some(value) = value {
-                    zelf.setitem(needle.to_pyobject(vm), value, vm)
-                } else {
-                    //zelf.internal_delitem(needle, vm)
-                    Err(vm.new_exception_msg(
-                            vm.ctx.exceptions.runtime_error.to_owned(),
-                            "Arrays do not support delete".to_string(),
-                    ))
-                }
-            }),
-            length: atomic_func!(|mapping, _vm| {
-                let zelf = $dtype::mapping_downcast(mapping);
-                Ok(zelf.arr.length())
-            }),
-        };
-        &AS_MAPPING
+/// Renders the accumulated `PlotCommand`s onto `area` (either the egui
+/// backend in the output panel or an `SVGBackend` for export).
+pub fn draw_plots<DB: DrawingBackend>(
+    area: &DrawingArea<DB, plotters::coord::Shift>,
+    commands: &[PlotCommand],
+) -> Result<(), Box<dyn std::error::Error + '_>>
+where
+    DB::ErrorType: 'static,
+{
+    area.fill(&plotters::style::WHITE)?;
+
+    let mut x_range = f64::INFINITY..f64::NEG_INFINITY;
+    let mut y_range = f64::INFINITY..f64::NEG_INFINITY;
+    let mut extend = |xs: &[f64], ys: &[f64]| {
+        for &v in xs {
+            x_range = x_range.start.min(v)..x_range.end.max(v);
+        }
+        for &v in ys {
+            y_range = y_range.start.min(v)..y_range.end.max(v);
+        }
+    };
+    for cmd in commands {
+        match cmd {
+            PlotCommand::PlotXY { x, y, .. } | PlotCommand::Scatter { x, y, .. } => {
+                extend(&to_vec(x), &to_vec(y))
+            }
+            PlotCommand::Bar { x, heights, .. } => extend(&to_vec(x), &to_vec(heights)),
+            PlotCommand::Hist { values, bins, .. } => {
+                let (edges, counts) = histogram(&to_vec(values), *bins);
+                extend(&edges, &counts)
+            }
+            PlotCommand::Title(_)
+            | PlotCommand::XLabel(_)
+            | PlotCommand::YLabel(_)
+            | PlotCommand::XLim(..)
+            | PlotCommand::YLim(..)
+            | PlotCommand::Legend => {}
+        }
     }
-}
-
-impl AsNumber for $dtype {
-    fn as_number() -> &'static rustpython_vm::protocol::PyNumberMethods {
-        static AS_MAPPING: PyNumberMethods = PyNumberMethods {
-            inplace_add: Some(|a, b, vm| {
-                $dtype::iadd(
-                    $dtype::number_downcast_exact(a.to_number(), vm),
-                    b.to_owned(),
-                    vm,
-                )?;
-                Ok(a.to_owned())
-            }),
-            add: Some(|a, b, vm| {
-                $dtype::add(
-                    $dtype::number_downcast_exact(a.to_number(), vm),
-                    b.to_owned(),
-                    vm,
-                )
-            }),
-
-            inplace_multiply: Some(|a, b, vm| {
-                $dtype::imul(
-                    $dtype::number_downcast_exact(a.to_number(), vm),
-                    b.to_owned(),
-                    vm,
-                )?;
-                Ok(a.to_owned())
-            }),
-            multiply: Some(|a, b, vm| {
-                $dtype::mul(
-                    $dtype::number_downcast_exact(a.to_number(), vm),
-                    b.to_owned(),
-                    vm,
-                )
-            }),
-
-            inplace_true_divide: Some(|a, b, vm| {
-                $dtype::itruediv(
-                    $dtype::number_downcast_exact(a.to_number(), vm),
-                    b.to_owned(),
-                    vm,
-                )?;
-                Ok(a.to_owned())
-            }),
-            true_divide: Some(|a, b, vm| {
-                $dtype::truediv(
-                    $dtype::number_downcast_exact(a.to_number(), vm),
-                    b.to_owned(),
-                    vm,
-                )
-            }),
-
-            inplace_subtract: Some(|a, b, vm| {
-                $dtype::isub(
-                    $dtype::number_downcast_exact(a.to_number(), vm),
-                    b.to_owned(),
-                    vm,
-                )?;
-                Ok(a.to_owned())
-            }),
-            subtract: Some(|a, b, vm| {
-                $dtype::sub(
-                    $dtype::number_downcast_exact(a.to_number(), vm),
-                    b.to_owned(),
-                    vm,
-                )
-            }),
-
-            ..PyNumberMethods::NOT_IMPLEMENTED
-        };
-        &AS_MAPPING
+    if x_range.is_empty() || y_range.is_empty() {
+        return Ok(());
     }
-}
 
-impl From<SlicedArcArray<$primitive>> for $dtype {
-    fn from(arr: SlicedArcArray<$primitive>) -> Self {
-        Self { arr }
+    // An explicit `xlim`/`ylim` overrides the data-driven auto range.
+    if let Some((lo, hi)) = commands.iter().find_map(|c| match c {
+        PlotCommand::XLim(lo, hi) => Some((*lo, *hi)),
+        _ => None,
+    }) {
+        x_range = lo..hi;
+    }
+    if let Some((lo, hi)) = commands.iter().find_map(|c| match c {
+        PlotCommand::YLim(lo, hi) => Some((*lo, *hi)),
+        _ => None,
+    }) {
+        y_range = lo..hi;
     }
-}
-};
-}
 
-build_pyarray!(f32, PyNdArrayFloat32, DataType::Float32);
-build_pyarray!(f64, PyNdArrayFloat64, DataType::Float64);
-
-#[pyfunction]
-fn zeros(shape: PyObjectRef, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
-    let dtype = kw.pop_kwarg("dtype");
-
-    let shape = py_shape_to_rust(shape.into(), vm)?;
-
-    let dtype = dtype
-        .map(|dtype| {
-            DataType::from_pyobject(&dtype)
-                .ok_or_else(|| vm.new_runtime_error(format!("Unrecognized dtype {dtype:?}")))
-        })
-    .transpose()?;
-
-    match dtype {
-        Some(DataType::Float64) => Ok(PyNdArrayFloat64::from(SlicedArcArray::from_array(
-                    ndarray::ArrayD::zeros(shape),
-        ))
-            .to_pyobject(vm)),
-        None | Some(DataType::Float32) => Ok(PyNdArrayFloat32::from(
-                SlicedArcArray::from_array(ndarray::ArrayD::zeros(shape)),
-        )
-            .to_pyobject(vm)),
+    let title = commands.iter().find_map(|c| match c {
+        PlotCommand::Title(s) => Some(s.as_str()),
+        _ => None,
+    });
+    let mut builder = ChartBuilder::on(area);
+    builder.margin(10).x_label_area_size(30).y_label_area_size(30);
+    if let Some(title) = title {
+        builder.caption(title, ("sans-serif", 20));
+    }
+    let mut chart = builder.build_cartesian_2d(x_range, y_range)?;
+
+    let xlabel = commands.iter().find_map(|c| match c {
+        PlotCommand::XLabel(s) => Some(s.as_str()),
+        _ => None,
+    });
+    let ylabel = commands.iter().find_map(|c| match c {
+        PlotCommand::YLabel(s) => Some(s.as_str()),
+        _ => None,
+    });
+    chart
+        .configure_mesh()
+        .x_desc(xlabel.unwrap_or(""))
+        .y_desc(ylabel.unwrap_or(""))
+        .draw()?;
+
+    let mut palette = [RED, BLUE, GREEN, CYAN, MAGENTA].into_iter().cycle();
+    let mut any_labeled = false;
+
+    for cmd in commands {
+        match cmd {
+            PlotCommand::PlotXY { x, y, style } => {
+                let color = style.color_or(palette.next().unwrap());
+                let series = chart.draw_series(LineSeries::new(
+                    to_vec(x).into_iter().zip(to_vec(y)),
+                    color.stroke_width(2),
+                ))?;
+                if let Some(label) = &style.label {
+                    any_labeled = true;
+                    series
+                        .label(label)
+                        .legend(move |(x, y)| plotters::prelude::PathElement::new(vec![(x, y), (x + 20, y)], color));
+                }
+            }
+            PlotCommand::Scatter { x, y, style } => {
+                let color = style.color_or(palette.next().unwrap());
+                let series = chart.draw_series(
+                    to_vec(x)
+                        .into_iter()
+                        .zip(to_vec(y))
+                        .map(|(x, y)| plotters::prelude::Circle::new((x, y), 3, color.filled())),
+                )?;
+                if let Some(label) = &style.label {
+                    any_labeled = true;
+                    series
+                        .label(label)
+                        .legend(move |(x, y)| plotters::prelude::Circle::new((x + 10, y), 3, color.filled()));
+                }
+            }
+            PlotCommand::Bar { x, heights, style } => {
+                let color = style.color_or(palette.next().unwrap());
+                let series = chart.draw_series(to_vec(x).into_iter().zip(to_vec(heights)).map(
+                    |(x, h)| {
+                        plotters::prelude::Rectangle::new([(x - 0.4, 0.0), (x + 0.4, h)], color.filled())
+                    },
+                ))?;
+                if let Some(label) = &style.label {
+                    any_labeled = true;
+                    series
+                        .label(label)
+                        .legend(move |(x, y)| plotters::prelude::Rectangle::new([(x, y - 5), (x + 20, y + 5)], color.filled()));
+                }
+            }
+            PlotCommand::Hist { values, bins, style } => {
+                let color = style.color_or(palette.next().unwrap());
+                let (edges, counts) = histogram(&to_vec(values), *bins);
+                let series = chart.draw_series(counts.iter().enumerate().map(|(i, &count)| {
+                    plotters::prelude::Rectangle::new([(edges[i], 0.0), (edges[i + 1], count)], color.filled())
+                }))?;
+                if let Some(label) = &style.label {
+                    any_labeled = true;
+                    series
+                        .label(label)
+                        .legend(move |(x, y)| plotters::prelude::Rectangle::new([(x, y - 5), (x + 20, y + 5)], color.filled()));
+                }
+            }
+            PlotCommand::Title(_)
+            | PlotCommand::XLabel(_)
+            | PlotCommand::YLabel(_)
+            | PlotCommand::XLim(..)
+            | PlotCommand::YLim(..) => {}
+            PlotCommand::Legend => {}
+        }
     }
-}
 
-#[pyfunction]
-fn arange(
-    start_or_stop_a: PyRef<PyFloat>,
-    stop: OptionalArg<PyRef<PyFloat>>,
-    step: OptionalArg<PyRef<PyFloat>>,
-    mut kw: KwArgs,
-    vm: &VirtualMachine,
-) -> PyResult {
-    let dtype = kw.pop_kwarg("dtype");
-    let dtype = dtype
-        .map(|dtype| {
-            DataType::from_pyobject(&dtype)
-                .ok_or_else(|| vm.new_runtime_error(format!("Unrecognized dtype {dtype:?}")))
-        })
-    .transpose()?;
-    let dtype = dtype.unwrap_or(DataType::Float32);
-
-    let start_or_stop_a = start_or_stop_a.to_f64(); //pyint_to_isize(&start_or_stop_a, vm)?;
-    let stop = stop.as_option().map(|stop| stop.to_f64()); //pyint_to_isize(&stop, vm)).transpose()?;
-    let step = step.as_option().map(|step| step.to_f64()); //pyint_to_isize(&step, vm)).transpose()?;
-
-    let (start, stop, step) = match (stop, step) {
-        (None, None) => (0.0, start_or_stop_a, 1.0),
-        (Some(stop), None) => (start_or_stop_a, stop, 1.0),
-        (Some(stop), Some(step)) => (start_or_stop_a, stop, step),
-        _ => unreachable!(),
-    };
+    let wants_legend = commands.iter().any(|c| matches!(c, PlotCommand::Legend));
+    if wants_legend && any_labeled {
+        chart
+            .configure_series_labels()
+            .background_style(plotters::style::WHITE.mix(0.8))
+            .draw()?;
+    }
 
-    Ok(match dtype {
-        DataType::Float32 => SlicedArcArray::from_array(
-            ndarray::Array::range(start as f32, stop as f32, step as f32).into_dyn(),
-        )
-            .cast()
-            .to_pyobject(vm),
-        DataType::Float64 => {
-            SlicedArcArray::from_array(ndarray::Array::range(start, stop, step).into_dyn())
-                .cast()
-                .to_pyobject(vm)
-        }
-    })
+    area.present()?;
+    Ok(())
 }
-*/
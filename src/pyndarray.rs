@@ -0,0 +1,1457 @@
+//! Python-facing ndarray bindings: the `SlicedArcArray<T>` storage type, the
+//! `build_pyarray!` macro that stamps out one `pyclass` per dtype, and the
+//! module-level constructors (`zeros`, `arange`, ...).
+
+use std::sync::{Arc, RwLock};
+
+use ndarray::{ArrayD, ArrayViewD, ArrayViewMutD, IxDyn};
+
+use rustpython_vm::{
+    atomic_func,
+    builtins::{PyFloat, PyInt, PySlice, PyStrRef, PyTuple},
+    convert::ToPyObject,
+    function::{KwArgs, OptionalArg},
+    protocol::{AsMapping, AsNumber, PyMappingMethods, PyNumberMethods},
+    types::{Comparable, PyComparisonOp, PyComparisonValue},
+    PyObject, PyObjectRef, PyPayload, PyRef, PyResult, TryFromObject, VirtualMachine,
+};
+
+/// The dtypes this module knows how to hold. Each one corresponds to a
+/// `pyclass` generated by `build_pyarray!` below.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataType {
+    Float32,
+    Float64,
+    Int32,
+    Int64,
+    Bool,
+}
+
+impl DataType {
+    pub fn from_pyobject(obj: &PyObjectRef, vm: &VirtualMachine) -> Option<Self> {
+        match obj.str(vm).ok()?.to_string().as_str() {
+            "float32" => Some(DataType::Float32),
+            "float64" => Some(DataType::Float64),
+            "int32" => Some(DataType::Int32),
+            "int64" => Some(DataType::Int64),
+            "bool" => Some(DataType::Bool),
+            _ => None,
+        }
+    }
+}
+
+/// A primitive that can answer "is this truthy?" the way Python's `bool()`
+/// treats it, so `any`/`all` can fold over any dtype uniformly.
+pub trait Truthy: Copy {
+    fn is_truthy(self) -> bool;
+    fn as_f64(self) -> f64;
+}
+
+macro_rules! impl_truthy_numeric {
+    ($($t:ty),*) => {
+        $(impl Truthy for $t {
+            fn is_truthy(self) -> bool { self != 0 as $t }
+            fn as_f64(self) -> f64 { self as f64 }
+        })*
+    };
+}
+impl_truthy_numeric!(f32, f64, i32, i64);
+
+impl Truthy for bool {
+    fn is_truthy(self) -> bool {
+        self
+    }
+    fn as_f64(self) -> f64 {
+        if self {
+            1.0
+        } else {
+            0.0
+        }
+    }
+}
+
+/// Converts a `sum()` accumulator, still in the array's own numeric dtype,
+/// to the Python scalar `sum()` should return: an `int` for the integer
+/// dtypes (so large `i64` totals don't lose precision round-tripping
+/// through `f64`), a `float` for the floating ones.
+trait SumScalar: Copy {
+    fn sum_to_pyobject(self, vm: &VirtualMachine) -> PyObjectRef;
+}
+
+macro_rules! impl_sum_scalar_float {
+    ($($t:ty),*) => {
+        $(impl SumScalar for $t {
+            fn sum_to_pyobject(self, vm: &VirtualMachine) -> PyObjectRef {
+                vm.ctx.new_float(self as f64).into()
+            }
+        })*
+    };
+}
+impl_sum_scalar_float!(f32, f64);
+
+macro_rules! impl_sum_scalar_int {
+    ($($t:ty),*) => {
+        $(impl SumScalar for $t {
+            fn sum_to_pyobject(self, vm: &VirtualMachine) -> PyObjectRef {
+                vm.ctx.new_int(self).into()
+            }
+        })*
+    };
+}
+impl_sum_scalar_int!(i32, i64);
+
+/// Resolves a (possibly negative) Python axis index against `ndim`.
+fn normalize_axis(axis: isize, ndim: usize, vm: &VirtualMachine) -> PyResult<usize> {
+    let resolved = if axis < 0 { axis + ndim as isize } else { axis };
+    if resolved < 0 || resolved as usize >= ndim {
+        return Err(vm.new_value_error(format!("axis {axis} is out of bounds for {ndim}-d array")));
+    }
+    Ok(resolved as usize)
+}
+
+/// One axis of a resolved (start, stop, step) slice, in the *underlying*
+/// array's index space, already clamped/negative-resolved the way NumPy
+/// resolves `slice` objects. `step` may be negative.
+#[derive(Debug, Clone, Copy)]
+pub struct AxisSlice {
+    pub start: isize,
+    pub stop: isize,
+    pub step: isize,
+}
+
+impl AxisSlice {
+    /// Number of elements this axis slice covers.
+    pub fn len(&self) -> usize {
+        if self.step > 0 {
+            if self.stop <= self.start {
+                0
+            } else {
+                ((self.stop - self.start - 1) / self.step + 1) as usize
+            }
+        } else if self.start <= self.stop {
+            0
+        } else {
+            ((self.start - self.stop - 1) / (-self.step) + 1) as usize
+        }
+    }
+
+    fn to_ndarray_slice(self) -> ndarray::Slice {
+        // `ndarray::Slice` first restricts the axis to `start..end` and only
+        // then walks it by `step` (from its last element when `step` is
+        // negative), unlike NumPy's "start at `start`, walk towards `stop`".
+        // Handing it the tight range around exactly the `len()` selected
+        // elements covers negative steps, the `-1` "no lower bound" sentinel
+        // (e.g. whole-axis `a[::-1]`) and composed stops past the axis end.
+        let n = self.len() as isize;
+        if n == 0 {
+            return ndarray::Slice::new(0, Some(0), 1);
+        }
+        let last = self.start + (n - 1) * self.step;
+        if self.step > 0 {
+            ndarray::Slice::new(self.start, Some(last + 1), self.step)
+        } else {
+            ndarray::Slice::new(last, Some(self.start + 1), self.step)
+        }
+    }
+}
+
+/// A fully resolved, per-axis slice descriptor, one `AxisSlice` per array
+/// axis, produced by `py_index_to_sliceinfo` or `DynamicSlice::whole`.
+#[derive(Debug, Clone, Default)]
+pub struct DynamicSlice {
+    pub axes: Vec<AxisSlice>,
+}
+
+impl DynamicSlice {
+    pub fn whole(shape: &[usize]) -> Self {
+        Self {
+            axes: shape
+                .iter()
+                .map(|&len| AxisSlice {
+                    start: 0,
+                    stop: len as isize,
+                    step: 1,
+                })
+                .collect(),
+        }
+    }
+
+    fn shape(&self) -> Vec<usize> {
+        self.axes.iter().map(AxisSlice::len).collect()
+    }
+}
+
+/// Shared, `Arc`-backed storage for a single dtype's array data, plus the
+/// `DynamicSlice` this particular handle is a (possibly strided) view
+/// through. Cloning a `SlicedArcArray` is cheap (it just bumps the `Arc`
+/// and copies the small slice descriptor); `read`/`write` apply the slice
+/// and take the lock for the duration of the closure.
+pub struct SlicedArcArray<T> {
+    data: Arc<RwLock<ArrayD<T>>>,
+    slice: DynamicSlice,
+}
+
+impl<T> Clone for SlicedArcArray<T> {
+    fn clone(&self) -> Self {
+        Self {
+            data: self.data.clone(),
+            slice: self.slice.clone(),
+        }
+    }
+}
+
+impl<T: Clone + Default + 'static> SlicedArcArray<T> {
+    pub fn from_array(arr: ArrayD<T>) -> Self {
+        let slice = DynamicSlice::whole(arr.shape());
+        Self {
+            data: Arc::new(RwLock::new(arr)),
+            slice,
+        }
+    }
+
+    /// Composes `inner` (resolved against `self`'s own, already-sliced,
+    /// logical shape) with `self.slice` to get a `DynamicSlice` in terms of
+    /// the underlying raw array, so chained slicing (`a[::2][1:]`) works.
+    fn compose(&self, inner: &DynamicSlice) -> DynamicSlice {
+        DynamicSlice {
+            axes: self
+                .slice
+                .axes
+                .iter()
+                .zip(inner.axes.iter())
+                .map(|(outer, inner)| AxisSlice {
+                    start: outer.start + inner.start * outer.step,
+                    stop: outer.start + inner.stop * outer.step,
+                    step: outer.step * inner.step,
+                })
+                .collect(),
+        }
+    }
+
+    pub fn read<U>(&self, f: impl FnOnce(ArrayViewD<'_, T>) -> U) -> U {
+        let guard = self.data.read().unwrap();
+        let view = guard
+            .view()
+            .slice_each_axis(|ax| self.slice.axes[ax.axis.index()].to_ndarray_slice());
+        f(view)
+    }
+
+    pub fn write<U>(&self, f: impl FnOnce(ArrayViewMutD<'_, T>) -> U) -> U {
+        self.write_sliced(&DynamicSlice::whole(&self.shape()), f)
+    }
+
+    /// Like `write`, but further restricts the view to `slice` (resolved
+    /// against `self`'s own logical shape) before handing it to `f` — this
+    /// is what lets `setitem`/the in-place ops target a sub-region.
+    pub fn write_sliced<U>(
+        &self,
+        slice: &DynamicSlice,
+        f: impl FnOnce(ArrayViewMutD<'_, T>) -> U,
+    ) -> U {
+        let composed = self.compose(slice);
+        let mut guard = self.data.write().unwrap();
+        let view = guard
+            .view_mut()
+            .slice_each_axis_mut(|ax| composed.axes[ax.axis.index()].to_ndarray_slice());
+        f(view)
+    }
+
+    pub fn shape(&self) -> Vec<usize> {
+        self.slice.shape()
+    }
+
+    pub fn length(&self) -> usize {
+        self.shape().iter().product()
+    }
+
+    pub fn bounds_check(&self, slice: &DynamicSlice) -> Result<(), String> {
+        let shape = self.shape();
+        if slice.axes.len() != shape.len() {
+            return Err(format!(
+                "expected {} axes, got {}",
+                shape.len(),
+                slice.axes.len()
+            ));
+        }
+        Ok(())
+    }
+
+    /// A strided view sharing storage with `self`, restricted to `slice`
+    /// (resolved against `self`'s own logical shape). This is how
+    /// `getitem` returns `a[::2]`/`a[1:, ::3]` without copying.
+    pub fn sliced_view(&self, slice: DynamicSlice) -> Self {
+        Self {
+            data: self.data.clone(),
+            slice: self.compose(&slice),
+        }
+    }
+
+    /// A deep copy sharing no storage with `self`; backs the `copy()` method.
+    pub fn sliced_copy(&self) -> Self {
+        Self::from_array(self.read(|arr| arr.to_owned()))
+    }
+
+    /// Whether `self`'s current view is laid out C-contiguously, i.e. a
+    /// `reshape` can materialize straight from it without first forcing a
+    /// contiguous copy.
+    pub fn is_c_contiguous(&self) -> bool {
+        self.read(|arr| arr.is_standard_layout())
+    }
+
+    /// A C-contiguous copy of `self`, regardless of the current view.
+    pub fn ascontiguousarray(&self) -> Self {
+        Self::from_array(self.read(|arr| arr.as_standard_layout().to_owned()))
+    }
+
+    /// Reshapes `self` into `shape`, which must have the same total element
+    /// count. When `self` is already a C-contiguous view over the *whole*
+    /// backing array, and no other handle shares it, this just reshapes the
+    /// backing array in place and hands back a new view sharing the same
+    /// `Arc` — no copy. Otherwise (sliced, non-contiguous, or aliased) the
+    /// data is compacted into a fresh array first.
+    pub fn reshape(&self, shape: Vec<usize>, vm: &VirtualMachine) -> PyResult<Self> {
+        let total: usize = shape.iter().product();
+        if total == self.length() && self.is_c_contiguous() && Arc::strong_count(&self.data) == 1 {
+            let mut guard = self.data.write().unwrap();
+            if guard.len() == total {
+                let owned = std::mem::replace(&mut *guard, ArrayD::default(IxDyn(&[])));
+                *guard = owned
+                    .into_shape_with_order(IxDyn(&shape))
+                    .map_err(|e| vm.new_value_error(format!("cannot reshape array: {e}")))?;
+                drop(guard);
+                return Ok(Self {
+                    data: self.data.clone(),
+                    slice: DynamicSlice::whole(&shape),
+                });
+            }
+        }
+
+        let contiguous = self.read(|arr| arr.as_standard_layout().to_owned());
+        let reshaped = contiguous
+            .into_shape_with_order(IxDyn(&shape))
+            .map_err(|e| vm.new_value_error(format!("cannot reshape array: {e}")))?;
+        Ok(Self::from_array(reshaped))
+    }
+
+    pub fn to_string(&self) -> String
+    where
+        T: std::fmt::Display,
+    {
+        self.read(|arr| format!("{arr}"))
+    }
+
+    pub fn repr(&self) -> String
+    where
+        T: std::fmt::Debug,
+    {
+        self.read(|arr| format!("{arr:?}"))
+    }
+
+    /// Applies `assign_fn` over the sub-region of `self` described by
+    /// `slice`, broadcasting `other` (an array or a scalar) up to that
+    /// sub-region's shape if needed.
+    pub fn assign_fn<F>(
+        &self,
+        slice: DynamicSlice,
+        other: SlicedArcArray<T>,
+        vm: &VirtualMachine,
+        assign_fn: F,
+    ) -> PyResult<()>
+    where
+        F: Fn(ArrayViewMutD<'_, T>, ArrayViewD<'_, T>, &VirtualMachine) -> PyResult<()>,
+    {
+        let other_owned = other.read(|arr| arr.to_owned());
+        self.write_sliced(&slice, |dest| {
+            let broadcast = other_owned.view().broadcast(IxDyn(dest.shape())).ok_or_else(|| {
+                vm.new_value_error("operands could not be broadcast together".to_owned())
+            })?;
+            assign_fn(dest, broadcast, vm)
+        })
+    }
+}
+
+/// Computes the NumPy-style broadcast shape of two shapes, right-aligning
+/// axes and requiring each pair to be equal or one of them to be `1`.
+pub fn broadcast_shape(a: &[usize], b: &[usize], vm: &VirtualMachine) -> PyResult<Vec<usize>> {
+    let n = a.len().max(b.len());
+    let mut out = vec![1usize; n];
+    for i in 0..n {
+        let da = *a.iter().rev().nth(i).unwrap_or(&1);
+        let db = *b.iter().rev().nth(i).unwrap_or(&1);
+        let d = if da == db || db == 1 {
+            da
+        } else if da == 1 {
+            db
+        } else {
+            return Err(
+                vm.new_value_error("operands could not be broadcast together".to_owned())
+            );
+        };
+        out[n - 1 - i] = d;
+    }
+    Ok(out)
+}
+
+pub fn empty_slice_like<T: Clone + Default + 'static>(arr: &SlicedArcArray<T>) -> DynamicSlice {
+    DynamicSlice::whole(&arr.shape())
+}
+
+/// Parses a Python subscript (`a[needle]`) against `shape` into a
+/// `DynamicSlice`, NumPy-style: a bare int or a tuple of ints/slices, each
+/// axis independently resolved (negative `start`/`stop`/`step` honored,
+/// an out-of-range `stop` clamped rather than erroring). Missing trailing
+/// axes default to the whole axis.
+pub fn py_index_to_sliceinfo(
+    needle: PyObjectRef,
+    shape: &[usize],
+    vm: &VirtualMachine,
+) -> PyResult<DynamicSlice> {
+    let items: Vec<PyObjectRef> = match needle.downcast::<PyTuple>() {
+        Ok(tuple) => tuple.as_slice().to_vec(),
+        Err(needle) => vec![needle],
+    };
+
+    if items.len() > shape.len() {
+        return Err(vm.new_index_error("too many indices for array".to_owned()));
+    }
+
+    let axes = shape
+        .iter()
+        .enumerate()
+        .map(|(i, &len)| {
+            let len = len as isize;
+            match items.get(i) {
+                None => Ok(AxisSlice {
+                    start: 0,
+                    stop: len,
+                    step: 1,
+                }),
+                Some(item) => match item.downcast_ref::<PySlice>() {
+                    Some(slice) => resolve_slice(slice, len, vm),
+                    None => {
+                        let idx: isize = TryFromObject::try_from_object(vm, item.clone())?;
+                        let resolved = if idx < 0 { idx + len } else { idx };
+                        if resolved < 0 || resolved >= len {
+                            return Err(vm.new_index_error("index out of range".to_owned()));
+                        }
+                        Ok(AxisSlice {
+                            start: resolved,
+                            stop: resolved + 1,
+                            step: 1,
+                        })
+                    }
+                },
+            }
+        })
+        .collect::<PyResult<Vec<_>>>()?;
+
+    Ok(DynamicSlice { axes })
+}
+
+/// Resolves a single Python `slice` object against an axis of length `len`,
+/// the way NumPy does: missing bounds default based on the sign of `step`,
+/// negative bounds are offset by `len`, and an out-of-range `stop` clamps
+/// rather than erroring.
+fn resolve_slice(slice: &PySlice, len: isize, vm: &VirtualMachine) -> PyResult<AxisSlice> {
+    let step = slice
+        .step
+        .clone()
+        .map(|s| isize::try_from_object(vm, s))
+        .transpose()?
+        .unwrap_or(1);
+    if step == 0 {
+        return Err(vm.new_value_error("slice step cannot be zero".to_owned()));
+    }
+
+    let resolve = |v: isize| if v < 0 { v + len } else { v };
+    let (default_start, default_stop) = if step > 0 { (0, len) } else { (len - 1, -1) };
+
+    let start = slice
+        .start
+        .clone()
+        .map(|s| isize::try_from_object(vm, s))
+        .transpose()?
+        .map(resolve)
+        .unwrap_or(default_start)
+        .clamp(if step > 0 { 0 } else { -1 }, if step > 0 { len } else { len - 1 });
+
+    let stop = slice
+        .stop
+        .clone()
+        .map(|s| isize::try_from_object(vm, s))
+        .transpose()?
+        .map(resolve)
+        .unwrap_or(default_stop)
+        .clamp(if step > 0 { 0 } else { -1 }, len);
+
+    Ok(AxisSlice { start, stop, step })
+}
+
+pub fn py_shape_to_rust(shape: PyObjectRef, vm: &VirtualMachine) -> PyResult<Vec<usize>> {
+    if let Ok(i) = i64::try_from_object(vm, shape.clone()) {
+        return Ok(vec![i as usize]);
+    }
+    let dims: Vec<PyObjectRef> = shape.try_to_value(vm)?;
+    dims.into_iter()
+        .map(|d| i64::try_from_object(vm, d).map(|d| d as usize))
+        .collect()
+}
+
+/// Builds the `Vec` of values `ndarray::Array::range` would produce, for
+/// integer element types that (unlike `f32`/`f64`) don't implement `Float`
+/// and so can't go through `Array::range` directly.
+fn integer_range<T>(start: T, stop: T, step: T) -> Vec<T>
+where
+    T: Copy + PartialOrd + std::ops::AddAssign + Default,
+{
+    let mut out = Vec::new();
+    if step > T::default() {
+        let mut v = start;
+        while v < stop {
+            out.push(v);
+            v += step;
+        }
+    } else if step < T::default() {
+        let mut v = start;
+        while v > stop {
+            out.push(v);
+            v += step;
+        }
+    }
+    out
+}
+
+/// Like `py_shape_to_rust`, but for `reshape`: allows (at most) one `-1`
+/// dimension, inferred from `total_len` so its product divides evenly.
+pub fn py_reshape_shape_to_rust(
+    shape: PyObjectRef,
+    total_len: usize,
+    vm: &VirtualMachine,
+) -> PyResult<Vec<usize>> {
+    let dims: Vec<isize> = if let Ok(i) = isize::try_from_object(vm, shape.clone()) {
+        vec![i]
+    } else {
+        let items: Vec<PyObjectRef> = shape.try_to_value(vm)?;
+        items
+            .into_iter()
+            .map(|d| isize::try_from_object(vm, d))
+            .collect::<PyResult<_>>()?
+    };
+
+    let neg_count = dims.iter().filter(|&&d| d == -1).count();
+    if neg_count > 1 {
+        return Err(vm.new_value_error("can only specify one unknown dimension".to_owned()));
+    }
+
+    if neg_count == 0 {
+        let dims: Vec<usize> = dims.into_iter().map(|d| d as usize).collect();
+        let product: usize = dims.iter().product();
+        if product != total_len {
+            return Err(vm.new_value_error(format!(
+                "cannot reshape array of size {total_len} into shape {dims:?}"
+            )));
+        }
+        return Ok(dims);
+    }
+
+    let known: usize = dims.iter().filter(|&&d| d != -1).map(|&d| d as usize).product();
+    if known == 0 || total_len % known != 0 {
+        return Err(vm.new_value_error(format!(
+            "cannot reshape array of size {total_len} into the given shape"
+        )));
+    }
+    let inferred = total_len / known;
+    Ok(dims
+        .into_iter()
+        .map(|d| if d == -1 { inferred } else { d as usize })
+        .collect())
+}
+
+/// Stamps out a `pyclass` wrapping `SlicedArcArray<$primitive>`: indexing,
+/// `str`/`repr`, `reshape`/contiguity helpers, comparisons and `any`/`all`.
+/// Assignment goes through `assign_or_elem_fn` so broadcasting is handled in
+/// one place; the elementwise arithmetic is added by `impl_pyarray_arith!`.
+macro_rules! build_pyarray {
+    ($primitive:ty, $dtype:ident, $dtype_enum:expr, [$($with:ident),*]) => {
+        #[derive(PyPayload, Clone)]
+        #[rustpython_vm::pyclass(module = "pyndarray", name, with($($with),*))]
+        pub struct $dtype {
+            pub(crate) arr: SlicedArcArray<$primitive>,
+        }
+
+        impl $dtype {
+            pub const DTYPE: DataType = $dtype_enum;
+        }
+
+        #[rustpython_vm::pyclass]
+        impl $dtype {
+            #[pymethod(magic)]
+            fn len(&self, _vm: &VirtualMachine) -> PyResult<PyInt> {
+                Ok((self.arr.length() as i64).into())
+            }
+
+            #[pymethod(magic)]
+            fn str(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyResult<PyStrRef> {
+                Ok(vm.ctx.new_str(zelf.arr.to_string()))
+            }
+
+            #[pymethod(magic)]
+            fn repr(zelf: PyRef<Self>, vm: &VirtualMachine) -> PyResult<PyStrRef> {
+                Ok(vm.ctx.new_str(zelf.arr.repr()))
+            }
+
+            #[pymethod(magic)]
+            fn getitem(&self, needle: PyObjectRef, vm: &VirtualMachine) -> PyResult<Self> {
+                let slice = py_index_to_sliceinfo(needle, &self.arr.shape(), vm)?;
+                Ok(Self {
+                    arr: self.arr.sliced_view(slice),
+                })
+            }
+
+            #[pymethod(magic)]
+            fn setitem(
+                &self,
+                needle: PyObjectRef,
+                value: PyObjectRef,
+                vm: &VirtualMachine,
+            ) -> PyResult<()> {
+                let slice = py_index_to_sliceinfo(needle, &self.arr.shape(), vm)?;
+                self.assign_or_elem_fn(
+                    slice,
+                    value,
+                    vm,
+                    |mut dest, src, _vm| Ok(dest.assign(&src)),
+                    |mut dest, value, _vm| Ok(dest.fill(value)),
+                )
+            }
+
+            /// Reshapes to `shape`, which may contain one `-1` dimension to be
+            /// inferred. Non-contiguous (sliced) data is compacted first.
+            #[pymethod]
+            fn reshape(&self, shape: PyObjectRef, vm: &VirtualMachine) -> PyResult<Self> {
+                let shape = py_reshape_shape_to_rust(shape, self.arr.length(), vm)?;
+                Ok(Self {
+                    arr: self.arr.reshape(shape, vm)?,
+                })
+            }
+
+            #[pymethod]
+            fn is_c_contiguous(&self, _vm: &VirtualMachine) -> PyResult<bool> {
+                Ok(self.arr.is_c_contiguous())
+            }
+
+            #[pymethod]
+            fn ascontiguousarray(&self, _vm: &VirtualMachine) -> PyResult<Self> {
+                Ok(Self {
+                    arr: self.arr.ascontiguousarray(),
+                })
+            }
+
+            #[pymethod]
+            fn copy(&self, _vm: &VirtualMachine) -> PyResult<Self> {
+                Ok(Self {
+                    arr: self.arr.sliced_copy(),
+                })
+            }
+
+            #[pymethod(magic)]
+            fn lt(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyNdArrayBool> {
+                $dtype::compare(&zelf, &other, vm, |a, b| a < b)
+            }
+
+            #[pymethod(magic)]
+            fn le(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyNdArrayBool> {
+                $dtype::compare(&zelf, &other, vm, |a, b| a <= b)
+            }
+
+            #[pymethod(magic)]
+            fn gt(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyNdArrayBool> {
+                $dtype::compare(&zelf, &other, vm, |a, b| a > b)
+            }
+
+            #[pymethod(magic)]
+            fn ge(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyNdArrayBool> {
+                $dtype::compare(&zelf, &other, vm, |a, b| a >= b)
+            }
+
+            #[pymethod(magic)]
+            fn eq(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyNdArrayBool> {
+                $dtype::compare(&zelf, &other, vm, |a, b| a == b)
+            }
+
+            #[pymethod(magic)]
+            fn ne(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<PyNdArrayBool> {
+                $dtype::compare(&zelf, &other, vm, |a, b| a != b)
+            }
+
+            /// Folds `any`/`all` (logical or/and) over the given `axis`, or the
+            /// whole array when no axis is given.
+            #[pymethod]
+            fn any(&self, axis: OptionalArg<isize>, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+                self.reduce_bool(axis, false, |acc, v| acc || v.is_truthy(), vm)
+            }
+
+            #[pymethod]
+            fn all(&self, axis: OptionalArg<isize>, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+                self.reduce_bool(axis, true, |acc, v| acc && v.is_truthy(), vm)
+            }
+
+            // `sum` lives in `impl_pyarray_arith!`/the `PyNdArrayBool` impl below,
+            // not here: numeric dtypes accumulate (and return) their own
+            // dtype, which bool can't do (there's no meaningful `bool` sum).
+        }
+
+        impl $dtype {
+            /// Shared body for the comparison magic methods: broadcasts `zelf`
+            /// and `other` together and applies `op` elementwise.
+            fn compare(
+                zelf: &PyRef<Self>,
+                other: &PyObjectRef,
+                vm: &VirtualMachine,
+                op: impl Fn($primitive, $primitive) -> bool,
+            ) -> PyResult<PyNdArrayBool> {
+                let rhs = match other.downcast_ref::<$dtype>() {
+                    Some(o) => o.arr.clone(),
+                    None => {
+                        let scalar: $primitive = TryFromObject::try_from_object(vm, other.clone())?;
+                        SlicedArcArray::from_array(ndarray::ArrayD::from_elem(IxDyn(&[]), scalar))
+                    }
+                };
+                let out_shape = broadcast_shape(&zelf.arr.shape(), &rhs.shape(), vm)?;
+                let result = zelf.arr.read(|lhs| {
+                    rhs.read(|rhs| {
+                        let lhs = lhs.broadcast(IxDyn(&out_shape)).unwrap();
+                        let rhs = rhs.broadcast(IxDyn(&out_shape)).unwrap();
+                        ndarray::Zip::from(&lhs)
+                            .and(&rhs)
+                            .map_collect(|&a, &b| op(a, b))
+                    })
+                });
+                Ok(PyNdArrayBool::from(SlicedArcArray::from_array(result)))
+            }
+
+            /// Shared body for `any`/`all`.
+            fn reduce_bool(
+                &self,
+                axis: OptionalArg<isize>,
+                init: bool,
+                op: impl Fn(bool, $primitive) -> bool,
+                vm: &VirtualMachine,
+            ) -> PyResult<PyObjectRef> {
+                match axis.into_option() {
+                    None => {
+                        let result = self.arr.read(|arr| arr.iter().fold(init, |acc, &v| op(acc, v)));
+                        Ok(vm.ctx.new_bool(result).into())
+                    }
+                    Some(axis) => {
+                        let reduced = self.arr.read(|arr| {
+                            let axis = normalize_axis(axis, arr.ndim(), vm)?;
+                            Ok(arr.fold_axis(ndarray::Axis(axis), init, |&acc, &v| op(acc, v)))
+                        })?;
+                        Ok(PyNdArrayBool::from(SlicedArcArray::from_array(reduced)).to_pyobject(vm))
+                    }
+                }
+            }
+        }
+
+        impl $dtype {
+            pub fn assign_or_elem_fn<F, G, U>(
+                &self,
+                slice: DynamicSlice,
+                value: PyObjectRef,
+                vm: &VirtualMachine,
+                assign_fn: F,
+                elem_fn: G,
+            ) -> PyResult<U>
+            where
+                F: Fn(
+                    ArrayViewMutD<'_, $primitive>,
+                    ArrayViewD<'_, $primitive>,
+                    &VirtualMachine,
+                ) -> PyResult<U>,
+                G: Fn(ArrayViewMutD<'_, $primitive>, $primitive, &VirtualMachine) -> PyResult<U>,
+            {
+                if let Some(other_array) = value.downcast_ref::<$dtype>() {
+                    self.arr
+                        .assign_fn(slice, other_array.arr.clone(), vm, assign_fn)
+                } else {
+                    let value: $primitive = TryFromObject::try_from_object(vm, value)?;
+                    if let Err(e) = self.arr.bounds_check(&slice) {
+                        return Err(vm.new_value_error(format!("Slice out of bounds; {e}")));
+                    }
+                    self.arr.write_sliced(&slice, |dest| elem_fn(dest, value, vm))
+                }
+            }
+        }
+
+        impl From<SlicedArcArray<$primitive>> for $dtype {
+            fn from(arr: SlicedArcArray<$primitive>) -> Self {
+                Self { arr }
+            }
+        }
+
+        impl AsMapping for $dtype {
+            fn as_mapping() -> &'static PyMappingMethods {
+                static AS_MAPPING: PyMappingMethods = PyMappingMethods {
+                    subscript: atomic_func!(|mapping, needle, vm| {
+                        $dtype::mapping_downcast(mapping).getitem(needle.to_pyobject(vm), vm)
+                    }),
+                    ass_subscript: atomic_func!(|mapping, needle, value, vm| {
+                        let zelf = $dtype::mapping_downcast(mapping);
+                        if let Some(value) = value {
+                            zelf.setitem(needle.to_pyobject(vm), value, vm)
+                        } else {
+                            Err(vm.new_runtime_error("Arrays do not support delete".to_owned()))
+                        }
+                    }),
+                    length: atomic_func!(|mapping, _vm| {
+                        Ok($dtype::mapping_downcast(mapping).arr.length())
+                    }),
+                };
+                &AS_MAPPING
+            }
+        }
+
+        // `#[pymethod(magic)]` only adds `__lt__`/`__eq__`/... to the type's
+        // dict, which is enough for explicit `a.__lt__(b)` calls but not for
+        // `a < b` operator syntax — that goes through the richcompare slot,
+        // which has to be wired up separately here.
+        impl Comparable for $dtype {
+            fn cmp(
+                zelf: &PyRef<Self>,
+                other: &PyObject,
+                op: PyComparisonOp,
+                vm: &VirtualMachine,
+            ) -> PyResult<PyComparisonValue> {
+                let other = other.to_owned();
+                let result = match op {
+                    PyComparisonOp::Lt => $dtype::compare(zelf, &other, vm, |a, b| a < b)?,
+                    PyComparisonOp::Le => $dtype::compare(zelf, &other, vm, |a, b| a <= b)?,
+                    PyComparisonOp::Gt => $dtype::compare(zelf, &other, vm, |a, b| a > b)?,
+                    PyComparisonOp::Ge => $dtype::compare(zelf, &other, vm, |a, b| a >= b)?,
+                    PyComparisonOp::Eq => $dtype::compare(zelf, &other, vm, |a, b| a == b)?,
+                    PyComparisonOp::Ne => $dtype::compare(zelf, &other, vm, |a, b| a != b)?,
+                };
+                Ok(PyComparisonValue::Implemented(result.to_pyobject(vm)))
+            }
+        }
+    };
+}
+
+build_pyarray!(f32, PyNdArrayFloat32, DataType::Float32, [AsMapping, Comparable, AsNumber]);
+build_pyarray!(f64, PyNdArrayFloat64, DataType::Float64, [AsMapping, Comparable, AsNumber]);
+build_pyarray!(i32, PyNdArrayInt32, DataType::Int32, [AsMapping, Comparable, AsNumber]);
+build_pyarray!(i64, PyNdArrayInt64, DataType::Int64, [AsMapping, Comparable, AsNumber]);
+build_pyarray!(bool, PyNdArrayBool, DataType::Bool, [AsMapping, Comparable]);
+
+#[rustpython_vm::pyclass]
+impl PyNdArrayBool {
+    /// Counts `True` elements over the given `axis`, or the whole array when
+    /// no axis is given. Unlike the numeric dtypes' `sum`, this can't return
+    /// a same-dtype array (a count isn't a `bool`), so it always returns a
+    /// Python `int` (no axis) or a `PyNdArrayInt64` (axis), matching NumPy.
+    #[pymethod]
+    fn sum(&self, axis: OptionalArg<isize>, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        match axis.into_option() {
+            None => {
+                let total = self
+                    .arr
+                    .read(|arr| arr.iter().fold(0i64, |acc, v| acc + v.as_f64() as i64));
+                Ok(vm.ctx.new_int(total).into())
+            }
+            Some(axis) => {
+                let reduced = self.arr.read(|arr| {
+                    let axis = normalize_axis(axis, arr.ndim(), vm)?;
+                    Ok(arr.fold_axis(ndarray::Axis(axis), 0i64, |&acc, v| {
+                        acc + v.as_f64() as i64
+                    }))
+                })?;
+                Ok(PyNdArrayInt64::from(SlicedArcArray::from_array(reduced)).to_pyobject(vm))
+            }
+        }
+    }
+}
+
+/// Elementwise `+ - * /` (and their in-place forms), for the dtypes whose
+/// primitive implements `AddAssign`/`SubAssign`/`MulAssign`/`DivAssign` —
+/// `bool` does not, so it only gets indexing/compare/reduce ops from
+/// `build_pyarray!` above, none of this.
+macro_rules! impl_pyarray_arith {
+    ($primitive:ty, $dtype:ident) => {
+        #[rustpython_vm::pyclass]
+        impl $dtype {
+            #[pymethod(magic)]
+            fn iadd(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+                let empty_slice = empty_slice_like(&zelf.arr);
+                zelf.assign_or_elem_fn(
+                    empty_slice,
+                    other,
+                    vm,
+                    |mut dest, src, _vm| Ok(dest += &src),
+                    |mut dest, value, _vm| Ok(dest += value),
+                )
+            }
+
+            #[pymethod(magic)]
+            fn add(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                let out_shape = $dtype::broadcast_result_shape(&zelf, &other, vm)?;
+                let inst = $dtype {
+                    arr: SlicedArcArray::from_array(ndarray::ArrayD::default(
+                        ndarray::IxDyn(&out_shape),
+                    )),
+                };
+                let inst = inst.into_ref(&vm.ctx);
+                // Seed the destination with `zelf`'s data (broadcast up to `out_shape`)
+                // before accumulating `other` in place, so e.g. `(3,) + (100,3)` works.
+                inst.assign_or_elem_fn(
+                    empty_slice_like(&inst.arr),
+                    zelf.clone().into(),
+                    vm,
+                    |mut dest, src, _vm| Ok(dest.assign(&src)),
+                    |mut dest, value, _vm| Ok(dest.fill(value)),
+                )?;
+                $dtype::iadd(inst.clone(), other, vm)?;
+                Ok(inst.into())
+            }
+
+            #[pymethod(magic)]
+            fn isub(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+                let empty_slice = empty_slice_like(&zelf.arr);
+                zelf.assign_or_elem_fn(
+                    empty_slice,
+                    other,
+                    vm,
+                    |mut dest, src, _vm| Ok(dest -= &src),
+                    |mut dest, value, _vm| Ok(dest -= value),
+                )
+            }
+
+            #[pymethod(magic)]
+            fn sub(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                let out_shape = $dtype::broadcast_result_shape(&zelf, &other, vm)?;
+                let inst = $dtype {
+                    arr: SlicedArcArray::from_array(ndarray::ArrayD::default(
+                        ndarray::IxDyn(&out_shape),
+                    )),
+                };
+                let inst = inst.into_ref(&vm.ctx);
+                inst.assign_or_elem_fn(
+                    empty_slice_like(&inst.arr),
+                    zelf.clone().into(),
+                    vm,
+                    |mut dest, src, _vm| Ok(dest.assign(&src)),
+                    |mut dest, value, _vm| Ok(dest.fill(value)),
+                )?;
+                $dtype::isub(inst.clone(), other, vm)?;
+                Ok(inst.into())
+            }
+
+            #[pymethod(magic)]
+            fn itruediv(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+                let empty_slice = empty_slice_like(&zelf.arr);
+                zelf.assign_or_elem_fn(
+                    empty_slice,
+                    other,
+                    vm,
+                    |mut dest, src, _vm| Ok(dest /= &src),
+                    |mut dest, value, _vm| Ok(dest /= value),
+                )
+            }
+
+            #[pymethod(magic)]
+            fn truediv(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                let out_shape = $dtype::broadcast_result_shape(&zelf, &other, vm)?;
+                let inst = $dtype {
+                    arr: SlicedArcArray::from_array(ndarray::ArrayD::default(
+                        ndarray::IxDyn(&out_shape),
+                    )),
+                };
+                let inst = inst.into_ref(&vm.ctx);
+                inst.assign_or_elem_fn(
+                    empty_slice_like(&inst.arr),
+                    zelf.clone().into(),
+                    vm,
+                    |mut dest, src, _vm| Ok(dest.assign(&src)),
+                    |mut dest, value, _vm| Ok(dest.fill(value)),
+                )?;
+                $dtype::itruediv(inst.clone(), other, vm)?;
+                Ok(inst.into())
+            }
+
+            #[pymethod(magic)]
+            fn imul(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<()> {
+                let empty_slice = empty_slice_like(&zelf.arr);
+                zelf.assign_or_elem_fn(
+                    empty_slice,
+                    other,
+                    vm,
+                    |mut dest, src, _vm| Ok(dest *= &src),
+                    |mut dest, value, _vm| Ok(dest *= value),
+                )
+            }
+
+            #[pymethod(magic)]
+            fn mul(zelf: PyRef<Self>, other: PyObjectRef, vm: &VirtualMachine) -> PyResult {
+                let out_shape = $dtype::broadcast_result_shape(&zelf, &other, vm)?;
+                let inst = $dtype {
+                    arr: SlicedArcArray::from_array(ndarray::ArrayD::default(
+                        ndarray::IxDyn(&out_shape),
+                    )),
+                };
+                let inst = inst.into_ref(&vm.ctx);
+                inst.assign_or_elem_fn(
+                    empty_slice_like(&inst.arr),
+                    zelf.clone().into(),
+                    vm,
+                    |mut dest, src, _vm| Ok(dest.assign(&src)),
+                    |mut dest, value, _vm| Ok(dest.fill(value)),
+                )?;
+                $dtype::imul(inst.clone(), other, vm)?;
+                Ok(inst.into())
+            }
+
+            /// Accumulates `sum` over the given `axis`, or the whole array
+            /// when no axis is given, in the array's own dtype: an integer
+            /// dtype's sum stays an integer (no precision loss rounding
+            /// through `f64`), returned as a Python `int` (no axis) or a
+            /// same-dtype array (axis).
+            #[pymethod]
+            fn sum(&self, axis: OptionalArg<isize>, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+                match axis.into_option() {
+                    None => {
+                        let total: $primitive = self
+                            .arr
+                            .read(|arr| arr.iter().fold(<$primitive>::default(), |acc, v| acc + v));
+                        Ok(total.sum_to_pyobject(vm))
+                    }
+                    Some(axis) => {
+                        let reduced = self.arr.read(|arr| {
+                            let axis = normalize_axis(axis, arr.ndim(), vm)?;
+                            Ok(arr.fold_axis(ndarray::Axis(axis), <$primitive>::default(), |&acc, v| {
+                                acc + v
+                            }))
+                        })?;
+                        Ok(Self::from(SlicedArcArray::from_array(reduced)).to_pyobject(vm))
+                    }
+                }
+            }
+        }
+
+        impl $dtype {
+            /// Shared body for `add`/`sub`/`mul`/`truediv`: the result shape is the
+            /// broadcast of `zelf`'s shape with `other`'s shape (or just `zelf`'s
+            /// shape, if `other` is a bare scalar).
+            fn broadcast_result_shape(
+                zelf: &PyRef<Self>,
+                other: &PyObjectRef,
+                vm: &VirtualMachine,
+            ) -> PyResult<Vec<usize>> {
+                let lhs = zelf.arr.shape();
+                match other.downcast_ref::<$dtype>() {
+                    Some(other) => broadcast_shape(&lhs, &other.arr.shape(), vm),
+                    None => Ok(lhs),
+                }
+            }
+        }
+    };
+}
+
+impl_pyarray_arith!(f32, PyNdArrayFloat32);
+impl_pyarray_arith!(f64, PyNdArrayFloat64);
+impl_pyarray_arith!(i32, PyNdArrayInt32);
+impl_pyarray_arith!(i64, PyNdArrayInt64);
+
+/// `dot`/`__matmul__`/`__rmatmul__`, for the dtypes whose primitive is
+/// `ndarray::LinalgScalar` — `bool` is not, so (like the elementwise
+/// arithmetic above) it is excluded from this instantiation entirely rather
+/// than merely `where`-gated, since `#[pymethod]` registration itself is
+/// unconditional regardless of a bound on the surrounding `impl`.
+macro_rules! impl_pyarray_matmul {
+    ($primitive:ty, $dtype:ident) => {
+        #[rustpython_vm::pyclass]
+        impl $dtype {
+            #[pymethod]
+            fn dot(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<Self> {
+                $dtype::matmul_impl(self, &other, vm)
+            }
+
+            #[pymethod(magic)]
+            fn matmul(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<Self> {
+                $dtype::matmul_impl(self, &other, vm)
+            }
+
+            #[pymethod(magic)]
+            fn rmatmul(&self, other: PyObjectRef, vm: &VirtualMachine) -> PyResult<Self> {
+                let other = other
+                    .downcast_ref::<$dtype>()
+                    .ok_or_else(|| vm.new_type_error("unsupported operand type for @".to_owned()))?;
+                $dtype::matmul_impl(other, &self.clone().into_ref(&vm.ctx).into(), vm)
+            }
+        }
+
+        impl $dtype {
+            /// Shared body for `dot`/`__matmul__`/`__rmatmul__`: 1-D·1-D is an
+            /// inner product (scalar result), 2-D·2-D a matrix product, and
+            /// 2-D·1-D / 1-D·2-D a matrix-vector product. Sliced operands are
+            /// compacted first since `.dot()` needs a contiguous view.
+            fn matmul_impl(zelf: &Self, other: &PyObjectRef, vm: &VirtualMachine) -> PyResult<Self> {
+                let other = other.downcast_ref::<$dtype>().ok_or_else(|| {
+                    vm.new_type_error("matmul operand must be an array of the same dtype".to_owned())
+                })?;
+
+                let lhs = zelf.arr.ascontiguousarray();
+                let rhs = other.arr.ascontiguousarray();
+                let lhs_shape = lhs.shape();
+                let rhs_shape = rhs.shape();
+                let mismatch = || {
+                    vm.new_value_error(format!(
+                        "shapes {lhs_shape:?} and {rhs_shape:?} not aligned for matmul"
+                    ))
+                };
+
+                let result: ArrayD<$primitive> = match (lhs_shape.len(), rhs_shape.len()) {
+                    (1, 1) => {
+                        let a = lhs.read(|a| a.to_owned()).into_dimensionality::<ndarray::Ix1>().unwrap();
+                        let b = rhs.read(|a| a.to_owned()).into_dimensionality::<ndarray::Ix1>().unwrap();
+                        if a.len() != b.len() {
+                            return Err(mismatch());
+                        }
+                        ndarray::arr0(a.dot(&b)).into_dyn()
+                    }
+                    (2, 2) => {
+                        let a = lhs.read(|a| a.to_owned()).into_dimensionality::<ndarray::Ix2>().unwrap();
+                        let b = rhs.read(|a| a.to_owned()).into_dimensionality::<ndarray::Ix2>().unwrap();
+                        if a.shape()[1] != b.shape()[0] {
+                            return Err(mismatch());
+                        }
+                        a.dot(&b).into_dyn()
+                    }
+                    (2, 1) => {
+                        let a = lhs.read(|a| a.to_owned()).into_dimensionality::<ndarray::Ix2>().unwrap();
+                        let b = rhs.read(|a| a.to_owned()).into_dimensionality::<ndarray::Ix1>().unwrap();
+                        if a.shape()[1] != b.len() {
+                            return Err(mismatch());
+                        }
+                        a.dot(&b).into_dyn()
+                    }
+                    (1, 2) => {
+                        let a = lhs.read(|a| a.to_owned()).into_dimensionality::<ndarray::Ix1>().unwrap();
+                        let b = rhs.read(|a| a.to_owned()).into_dimensionality::<ndarray::Ix2>().unwrap();
+                        if a.len() != b.shape()[0] {
+                            return Err(mismatch());
+                        }
+                        a.dot(&b).into_dyn()
+                    }
+                    _ => return Err(vm.new_value_error("matmul only supports 1-D and 2-D arrays".to_owned())),
+                };
+
+                Ok(Self::from(SlicedArcArray::from_array(result)))
+            }
+        }
+    };
+}
+
+impl_pyarray_matmul!(f32, PyNdArrayFloat32);
+impl_pyarray_matmul!(f64, PyNdArrayFloat64);
+impl_pyarray_matmul!(i32, PyNdArrayInt32);
+impl_pyarray_matmul!(i64, PyNdArrayInt64);
+
+macro_rules! impl_pyarray_as_number {
+    ($primitive:ty, $dtype:ident) => {
+        // Wires `+ - * /` and `+= -= *= /=` operator syntax into the number
+        // protocol slots; the dict-level `#[pymethod(magic)]` entries above
+        // cover explicit `a.__add__(b)` calls but are never consulted by the
+        // operator syntax itself. `matrix_multiply`/`inplace_matrix_multiply`
+        // wire up `a @ b`/`a @= b` the same way.
+        impl AsNumber for $dtype {
+            fn as_number() -> &'static PyNumberMethods {
+                static AS_NUMBER: PyNumberMethods = PyNumberMethods {
+                    add: atomic_func!(|number, other, vm| {
+                        $dtype::add($dtype::number_downcast(number).to_owned(), other.to_owned(), vm)
+                    }),
+                    subtract: atomic_func!(|number, other, vm| {
+                        $dtype::sub($dtype::number_downcast(number).to_owned(), other.to_owned(), vm)
+                    }),
+                    multiply: atomic_func!(|number, other, vm| {
+                        $dtype::mul($dtype::number_downcast(number).to_owned(), other.to_owned(), vm)
+                    }),
+                    true_divide: atomic_func!(|number, other, vm| {
+                        $dtype::truediv($dtype::number_downcast(number).to_owned(), other.to_owned(), vm)
+                    }),
+                    inplace_add: atomic_func!(|number, other, vm| {
+                        let zelf = $dtype::number_downcast(number).to_owned();
+                        $dtype::iadd(zelf.clone(), other.to_owned(), vm)?;
+                        Ok(zelf.into())
+                    }),
+                    inplace_subtract: atomic_func!(|number, other, vm| {
+                        let zelf = $dtype::number_downcast(number).to_owned();
+                        $dtype::isub(zelf.clone(), other.to_owned(), vm)?;
+                        Ok(zelf.into())
+                    }),
+                    inplace_multiply: atomic_func!(|number, other, vm| {
+                        let zelf = $dtype::number_downcast(number).to_owned();
+                        $dtype::imul(zelf.clone(), other.to_owned(), vm)?;
+                        Ok(zelf.into())
+                    }),
+                    inplace_true_divide: atomic_func!(|number, other, vm| {
+                        let zelf = $dtype::number_downcast(number).to_owned();
+                        $dtype::itruediv(zelf.clone(), other.to_owned(), vm)?;
+                        Ok(zelf.into())
+                    }),
+                    matrix_multiply: atomic_func!(|number, other, vm| {
+                        $dtype::matmul($dtype::number_downcast(number), other.to_owned(), vm)
+                            .map(|result| result.to_pyobject(vm))
+                    }),
+                    inplace_matrix_multiply: atomic_func!(|number, other, vm| {
+                        $dtype::matmul($dtype::number_downcast(number), other.to_owned(), vm)
+                            .map(|result| result.to_pyobject(vm))
+                    }),
+                    ..PyNumberMethods::NOT_IMPLEMENTED
+                };
+                &AS_NUMBER
+            }
+        }
+    };
+}
+
+impl_pyarray_as_number!(f32, PyNdArrayFloat32);
+impl_pyarray_as_number!(f64, PyNdArrayFloat64);
+impl_pyarray_as_number!(i32, PyNdArrayInt32);
+impl_pyarray_as_number!(i64, PyNdArrayInt64);
+
+#[rustpython_vm::pymodule]
+pub mod ndarray_module {
+    use super::*;
+
+    #[pyfunction]
+    fn zeros(shape: PyObjectRef, mut kw: KwArgs, vm: &VirtualMachine) -> PyResult<PyObjectRef> {
+        let dtype = kw.pop_kwarg("dtype");
+        let shape = py_shape_to_rust(shape, vm)?;
+        let dtype = dtype
+            .map(|dtype| {
+                DataType::from_pyobject(&dtype, vm)
+                    .ok_or_else(|| vm.new_value_error(format!("Unrecognized dtype {dtype:?}")))
+            })
+            .transpose()?
+            .unwrap_or(DataType::Float32);
+
+        Ok(match dtype {
+            DataType::Float32 => {
+                PyNdArrayFloat32::from(SlicedArcArray::from_array(ArrayD::zeros(IxDyn(&shape))))
+                    .to_pyobject(vm)
+            }
+            DataType::Float64 => {
+                PyNdArrayFloat64::from(SlicedArcArray::from_array(ArrayD::zeros(IxDyn(&shape))))
+                    .to_pyobject(vm)
+            }
+            DataType::Int32 => {
+                PyNdArrayInt32::from(SlicedArcArray::from_array(ArrayD::zeros(IxDyn(&shape))))
+                    .to_pyobject(vm)
+            }
+            DataType::Int64 => {
+                PyNdArrayInt64::from(SlicedArcArray::from_array(ArrayD::zeros(IxDyn(&shape))))
+                    .to_pyobject(vm)
+            }
+            DataType::Bool => {
+                PyNdArrayBool::from(SlicedArcArray::from_array(ArrayD::from_elem(
+                    IxDyn(&shape),
+                    false,
+                )))
+                .to_pyobject(vm)
+            }
+        })
+    }
+
+    #[pyfunction]
+    fn arange(
+        start_or_stop_a: PyRef<PyFloat>,
+        stop: OptionalArg<PyRef<PyFloat>>,
+        step: OptionalArg<PyRef<PyFloat>>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult {
+        let dtype = kw.pop_kwarg("dtype");
+        let dtype = dtype
+            .map(|dtype| {
+                DataType::from_pyobject(&dtype, vm)
+                    .ok_or_else(|| vm.new_value_error(format!("Unrecognized dtype {dtype:?}")))
+            })
+            .transpose()?
+            .unwrap_or(DataType::Float32);
+
+        let start_or_stop_a = start_or_stop_a.to_f64();
+        let stop = stop.into_option().map(|v| v.to_f64());
+        let step = step.into_option().map(|v| v.to_f64());
+
+        let (start, stop, step) = match (stop, step) {
+            (None, None) => (0.0, start_or_stop_a, 1.0),
+            (Some(stop), None) => (start_or_stop_a, stop, 1.0),
+            (Some(stop), Some(step)) => (start_or_stop_a, stop, step),
+            _ => unreachable!(),
+        };
+
+        Ok(match dtype {
+            DataType::Float64 => PyNdArrayFloat64::from(SlicedArcArray::from_array(
+                ndarray::Array::range(start, stop, step).into_dyn(),
+            ))
+            .to_pyobject(vm),
+            DataType::Int32 => {
+                let data = integer_range(start as i32, stop as i32, step as i32);
+                let arr = ArrayD::from_shape_vec(IxDyn(&[data.len()]), data)
+                    .map_err(|e| vm.new_value_error(e.to_string()))?;
+                PyNdArrayInt32::from(SlicedArcArray::from_array(arr)).to_pyobject(vm)
+            }
+            DataType::Int64 => {
+                let data = integer_range(start as i64, stop as i64, step as i64);
+                let arr = ArrayD::from_shape_vec(IxDyn(&[data.len()]), data)
+                    .map_err(|e| vm.new_value_error(e.to_string()))?;
+                PyNdArrayInt64::from(SlicedArcArray::from_array(arr)).to_pyobject(vm)
+            }
+            DataType::Bool => {
+                return Err(vm.new_value_error("arange does not support dtype=bool".to_owned()))
+            }
+            DataType::Float32 => PyNdArrayFloat32::from(SlicedArcArray::from_array(
+                ndarray::Array::range(start as f32, stop as f32, step as f32).into_dyn(),
+            ))
+            .to_pyobject(vm),
+        })
+    }
+
+    /// Allocates an array of `shape` filled with `fill_value`. `shape` may be
+    /// a bare int (shorthand for a 1-D shape) or a tuple/list of ints.
+    #[pyfunction]
+    fn full(
+        shape: PyObjectRef,
+        fill_value: PyObjectRef,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyObjectRef> {
+        let dtype = kw.pop_kwarg("dtype");
+        let shape = py_shape_to_rust(shape, vm)?;
+        let dtype = dtype
+            .map(|dtype| {
+                DataType::from_pyobject(&dtype, vm)
+                    .ok_or_else(|| vm.new_value_error(format!("Unrecognized dtype {dtype:?}")))
+            })
+            .transpose()?
+            .unwrap_or(DataType::Float32);
+
+        Ok(match dtype {
+            DataType::Float32 => {
+                let v: f32 = TryFromObject::try_from_object(vm, fill_value)?;
+                PyNdArrayFloat32::from(SlicedArcArray::from_array(ArrayD::from_elem(
+                    IxDyn(&shape),
+                    v,
+                )))
+                .to_pyobject(vm)
+            }
+            DataType::Float64 => {
+                let v: f64 = TryFromObject::try_from_object(vm, fill_value)?;
+                PyNdArrayFloat64::from(SlicedArcArray::from_array(ArrayD::from_elem(
+                    IxDyn(&shape),
+                    v,
+                )))
+                .to_pyobject(vm)
+            }
+            DataType::Int32 => {
+                let v: i32 = TryFromObject::try_from_object(vm, fill_value)?;
+                PyNdArrayInt32::from(SlicedArcArray::from_array(ArrayD::from_elem(
+                    IxDyn(&shape),
+                    v,
+                )))
+                .to_pyobject(vm)
+            }
+            DataType::Int64 => {
+                let v: i64 = TryFromObject::try_from_object(vm, fill_value)?;
+                PyNdArrayInt64::from(SlicedArcArray::from_array(ArrayD::from_elem(
+                    IxDyn(&shape),
+                    v,
+                )))
+                .to_pyobject(vm)
+            }
+            DataType::Bool => {
+                let v: bool = TryFromObject::try_from_object(vm, fill_value)?;
+                PyNdArrayBool::from(SlicedArcArray::from_array(ArrayD::from_elem(
+                    IxDyn(&shape),
+                    v,
+                )))
+                .to_pyobject(vm)
+            }
+        })
+    }
+
+    /// `num` evenly spaced samples over `[start, stop]` (or `[start, stop)`
+    /// when `endpoint=False`), matching `numpy.linspace`. Routed through the
+    /// same `dtype` dispatch as `zeros`/`full`/`arange`; defaults to
+    /// `float64` (not those functions' `float32`) to match NumPy's default.
+    #[pyfunction]
+    fn linspace(
+        start: PyRef<PyFloat>,
+        stop: PyRef<PyFloat>,
+        num: OptionalArg<PyRef<PyInt>>,
+        mut kw: KwArgs,
+        vm: &VirtualMachine,
+    ) -> PyResult<PyObjectRef> {
+        let endpoint = kw
+            .pop_kwarg("endpoint")
+            .map(|v| v.try_to_bool(vm))
+            .transpose()?
+            .unwrap_or(true);
+        let dtype = kw.pop_kwarg("dtype");
+        let dtype = dtype
+            .map(|dtype| {
+                DataType::from_pyobject(&dtype, vm)
+                    .ok_or_else(|| vm.new_value_error(format!("Unrecognized dtype {dtype:?}")))
+            })
+            .transpose()?
+            .unwrap_or(DataType::Float64);
+
+        let start = start.to_f64();
+        let stop = stop.to_f64();
+        let num = num
+            .into_option()
+            .map(|n| n.as_bigint().try_into().unwrap_or(50usize))
+            .unwrap_or(50);
+
+        let divisor = if endpoint {
+            (num.max(1) - 1) as f64
+        } else {
+            num as f64
+        };
+        let step = if divisor == 0.0 {
+            0.0
+        } else {
+            (stop - start) / divisor
+        };
+
+        let data: Vec<f64> = (0..num).map(|i| start + step * i as f64).collect();
+
+        Ok(match dtype {
+            DataType::Float32 => {
+                let arr = ArrayD::from_shape_vec(IxDyn(&[num]), data.iter().map(|&v| v as f32).collect())
+                    .map_err(|e| vm.new_value_error(e.to_string()))?;
+                PyNdArrayFloat32::from(SlicedArcArray::from_array(arr)).to_pyobject(vm)
+            }
+            DataType::Float64 => {
+                let arr = ArrayD::from_shape_vec(IxDyn(&[num]), data)
+                    .map_err(|e| vm.new_value_error(e.to_string()))?;
+                PyNdArrayFloat64::from(SlicedArcArray::from_array(arr)).to_pyobject(vm)
+            }
+            DataType::Int32 => {
+                let arr = ArrayD::from_shape_vec(IxDyn(&[num]), data.iter().map(|&v| v as i32).collect())
+                    .map_err(|e| vm.new_value_error(e.to_string()))?;
+                PyNdArrayInt32::from(SlicedArcArray::from_array(arr)).to_pyobject(vm)
+            }
+            DataType::Int64 => {
+                let arr = ArrayD::from_shape_vec(IxDyn(&[num]), data.iter().map(|&v| v as i64).collect())
+                    .map_err(|e| vm.new_value_error(e.to_string()))?;
+                PyNdArrayInt64::from(SlicedArcArray::from_array(arr)).to_pyobject(vm)
+            }
+            DataType::Bool => {
+                return Err(vm.new_value_error("linspace does not support dtype=bool".to_owned()))
+            }
+        })
+    }
+}
+
+pub use ndarray_module::make_module;